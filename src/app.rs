@@ -1,18 +1,26 @@
 use leptos::prelude::*;
-use leptos_meta::{provide_meta_context, Stylesheet, Title};
+use leptos_meta::{provide_meta_context, Link, Stylesheet, Title};
 use leptos_router::{
     components::{Route, Router, Routes},
-    ParamSegment, StaticSegment, WildcardSegment,
+    ParamSegment, SsrMode, StaticSegment, WildcardSegment,
 };
 
+use crate::config::use_site_config;
+use crate::routes::admin::{AdminEditorPage, AdminPage};
+use crate::routes::blog::authors::AuthorPage;
 use crate::routes::blog::blog_post::BlogPostPage;
 use crate::routes::blog::blog_section::BlogListPage;
+use crate::routes::blog::series::SeriesPage;
+use crate::routes::blog::tags::{TagIndexPage, TagPage};
 use crate::routes::home_page::HomePage;
+use crate::routes::page::PageRoute;
+use crate::routes::search::SearchPage;
 
 #[component]
 pub fn App() -> impl IntoView {
     // Provides context that manages stylesheets, titles, meta tags, etc.
     provide_meta_context();
+    let config = use_site_config();
 
     view! {
         // injects a stylesheet into the document <head>
@@ -20,7 +28,12 @@ pub fn App() -> impl IntoView {
         <Stylesheet id="leptos" href="/pkg/leptos-bplate.css"/>
 
         // sets the document title
-        <Title text="Welcome to Leptos"/>
+        <Title text=config.name/>
+
+        // lets feed readers discover the blog feeds
+        <Link rel="alternate" type_="application/rss+xml" title="RSS" href="/feed.xml"/>
+        <Link rel="alternate" type_="application/atom+xml" title="Atom" href="/atom.xml"/>
+        <Link rel="alternate" type_="application/feed+json" title="JSON Feed" href="/feed.json"/>
 
         // content for this welcome page
         <Router>
@@ -28,8 +41,16 @@ pub fn App() -> impl IntoView {
                 <Routes fallback=move || "Not found.">
                     <Route path=StaticSegment("") view=HomePage/>
                     <Route path=StaticSegment("blog") view=BlogListPage/>
+                    <Route path=(StaticSegment("blog"), StaticSegment("page"), ParamSegment("n")) view=BlogListPage ssr=SsrMode::Async/>
+                    <Route path=(StaticSegment("blog"), StaticSegment("tags")) view=TagIndexPage/>
+                    <Route path=(StaticSegment("blog"), StaticSegment("tag"), ParamSegment("tag")) view=TagPage/>
+                    <Route path=(StaticSegment("blog"), StaticSegment("author"), ParamSegment("id")) view=AuthorPage/>
+                    <Route path=(StaticSegment("blog"), StaticSegment("series"), ParamSegment("name")) view=SeriesPage/>
                     <Route path=(StaticSegment("blog"), ParamSegment("slug")) view=BlogPostPage/>
-                    <Route path=WildcardSegment("any") view=NotFound/>
+                    <Route path=StaticSegment("search") view=SearchPage/>
+                    <Route path=StaticSegment("admin") view=AdminPage/>
+                    <Route path=(StaticSegment("admin"), StaticSegment("edit")) view=AdminEditorPage/>
+                    <Route path=WildcardSegment("any") view=PageRoute ssr=SsrMode::Async/>
                 </Routes>
             </main>
         </Router>
@@ -51,7 +72,7 @@ pub fn App() -> impl IntoView {
 
 /// 404 - Not Found
 #[component]
-fn NotFound() -> impl IntoView {
+pub fn NotFound() -> impl IntoView {
     // set an HTTP status code 404
     // this is feature gated because it can only be done during
     // initial server-side rendering
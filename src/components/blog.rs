@@ -1,7 +1,13 @@
-use crate::models::blog::BlogPost;
+use crate::components::islands::mount_islands;
+use crate::components::structured_data::{self, JsonLd};
+use crate::config::use_site_config;
+use crate::models::author::{Author, AuthorLink};
+use crate::models::blog::{
+    slugify, BlogPost, BlogPostMeta, PostNeighbors, SeriesListing, TocEntry,
+};
 use leptos::prelude::*;
 use leptos::{component, IntoView};
-use leptos_meta::Meta;
+use leptos_meta::{Link, Meta};
 
 /// Header component for blog post with metadata
 #[component]
@@ -12,14 +18,10 @@ pub fn BlogPostHeader(post: BlogPost) -> impl IntoView {
                 <span>"← Back to Blog"</span>
             </a>
 
-            {post.meta.cover_image.clone().map(|img| {
+            {post.meta.cover_image.is_some().then(|| {
                 view! {
                     <div class="aspect-video overflow-hidden rounded-2xl mb-8">
-                        <img
-                            src=format!("/assets/{}", img)
-                            alt=post.meta.title.clone()
-                            class="w-full h-full object-cover"
-                        />
+                        <CoverImage post=post.meta.clone() eager=true />
                     </div>
                 }
             })}
@@ -29,17 +31,20 @@ pub fn BlogPostHeader(post: BlogPost) -> impl IntoView {
             </h1>
 
             <div class="flex items-center gap-4 text-gray-600 mb-6">
-                <span>{post.meta.author.clone()}</span>
+                <AuthorByline authors=post.meta.author_profiles.clone() />
+                <span class="text-gray-300">"•"</span>
+                <span>{post.meta.date.to_string()}</span>
                 <span class="text-gray-300">"•"</span>
-                <span>{post.meta.date.clone()}</span>
+                <span>{format!("{} min read", post.meta.reading_time)}</span>
             </div>
 
             <div class="flex flex-wrap gap-2 mb-8">
                 {post.meta.tags.iter().map(|tag| {
                     view! {
-                        <span class="px-3 py-1 bg-blue-100 text-blue-700 text-sm rounded-full">
-                            {tag.clone()}
-                        </span>
+                        <TagLink
+                            tag=tag.clone()
+                            class="px-3 py-1 bg-blue-100 text-blue-700 text-sm rounded-full hover:bg-blue-200 transition"
+                        />
                     }
                 }).collect::<Vec<_>>()}
             </div>
@@ -47,9 +52,311 @@ pub fn BlogPostHeader(post: BlogPost) -> impl IntoView {
     }
 }
 
-/// Meta tags component for SEO
+/// Names of a post's authors, each linking to their page
+#[component]
+pub fn AuthorByline(authors: Vec<Author>) -> impl IntoView {
+    let count = authors.len();
+
+    view! {
+        <span>
+            {authors.into_iter().enumerate().map(|(i, author)| {
+                let separator = match count - i {
+                    1 => "",
+                    2 => " and ",
+                    _ => ", ",
+                };
+                let href = author.href();
+                view! {
+                    <a href=href class="hover:text-blue-600 transition">
+                        {author.name}
+                    </a>
+                    {separator}
+                }
+            }).collect::<Vec<_>>()}
+        </span>
+    }
+}
+
+/// Round avatar image, or the author's initials when there is none.
+/// `class` sets the size.
+#[component]
+pub fn AuthorAvatar(author: Author, #[prop(into)] class: String) -> impl IntoView {
+    match author.avatar_url() {
+        Some(src) => view! {
+            <img
+                src=src
+                alt=author.name
+                class=format!("{} rounded-full object-cover shrink-0", class)
+            />
+        }.into_any(),
+        None => view! {
+            <div class=format!(
+                "{} rounded-full bg-blue-100 text-blue-700 font-bold flex items-center justify-center shrink-0",
+                class,
+            )>
+                {author.initials()}
+            </div>
+        }.into_any(),
+    }
+}
+
+/// Profile links of an author
+#[component]
+pub fn AuthorLinks(links: Vec<AuthorLink>) -> impl IntoView {
+    (!links.is_empty()).then(|| view! {
+        <ul class="flex flex-wrap gap-4 mt-3">
+            {links.into_iter().map(|link| view! {
+                <li>
+                    <a
+                        href=link.url
+                        target="_blank"
+                        rel="noopener noreferrer"
+                        class="text-sm text-blue-600 hover:text-blue-800 transition"
+                    >
+                        {link.label}
+                    </a>
+                </li>
+            }).collect::<Vec<_>>()}
+        </ul>
+    })
+}
+
+/// Author card shown below a post
+#[component]
+pub fn AuthorCard(author: Author) -> impl IntoView {
+    view! {
+        <div class="flex gap-5 p-6 bg-gray-50 border border-gray-200 rounded-2xl">
+            <AuthorAvatar author=author.clone() class="w-16 h-16 text-xl" />
+            <div class="min-w-0">
+                <p class="text-sm text-gray-500 mb-1">"Written by"</p>
+                <a href=author.href() class="text-lg font-bold text-gray-900 hover:text-blue-600 transition">
+                    {author.name.clone()}
+                </a>
+                {(!author.bio.is_empty()).then(|| view! {
+                    <p class="text-gray-600 mt-2">{author.bio.clone()}</p>
+                })}
+                <AuthorLinks links=author.links />
+            </div>
+        </div>
+    }
+}
+
+/// Box listing every part of the series a post belongs to, with the
+/// current one highlighted
+#[component]
+pub fn SeriesBox(listing: SeriesListing, #[prop(into)] current: String) -> impl IntoView {
+    let total = listing.posts.len();
+    let position = listing.position(&current);
+    let href = listing.href();
+
+    view! {
+        <nav class="mb-10 p-6 bg-blue-50 border border-blue-100 rounded-2xl" aria-label="Series">
+            <p class="text-sm text-gray-600 mb-3">
+                {match position {
+                    Some(i) => format!("Part {} of {} in ", i + 1, total),
+                    None => "Part of ".to_string(),
+                }}
+                <a href=href class="font-semibold text-blue-700 hover:text-blue-900 transition">
+                    {listing.name}
+                </a>
+            </p>
+            <ol class="space-y-1 list-decimal list-inside">
+                {listing.posts.into_iter().map(|post| {
+                    if post.slug == current {
+                        view! {
+                            <li class="font-semibold text-gray-900" aria-current="page">
+                                {post.title}
+                            </li>
+                        }.into_any()
+                    } else {
+                        view! {
+                            <li class="text-gray-600">
+                                <a href=format!("/blog/{}", post.slug) class="hover:text-blue-600 transition">
+                                    {post.title}
+                                </a>
+                            </li>
+                        }.into_any()
+                    }
+                }).collect::<Vec<_>>()}
+            </ol>
+        </nav>
+    }
+}
+
+/// Links to the previous and next part of a series
+#[component]
+pub fn SeriesNav(listing: SeriesListing, #[prop(into)] current: String) -> impl IntoView {
+    let position = listing.position(&current);
+    let prev = position
+        .and_then(|i| i.checked_sub(1))
+        .and_then(|i| listing.posts.get(i).cloned());
+    let next = position.and_then(|i| listing.posts.get(i + 1).cloned());
+
+    view! {
+        <nav class="mt-12 grid sm:grid-cols-2 gap-4" aria-label="Series navigation">
+            <div>
+                {prev.map(|post| view! {
+                    <a href=format!("/blog/{}", post.slug) class="block p-4 border border-gray-200 rounded-xl hover:border-blue-300 transition">
+                        <span class="block text-sm text-gray-500 mb-1">"← Previous part"</span>
+                        <span class="font-semibold text-gray-900">{post.title}</span>
+                    </a>
+                })}
+            </div>
+            <div class="sm:text-right">
+                {next.map(|post| view! {
+                    <a href=format!("/blog/{}", post.slug) class="block p-4 border border-gray-200 rounded-xl hover:border-blue-300 transition">
+                        <span class="block text-sm text-gray-500 mb-1">"Next part →"</span>
+                        <span class="font-semibold text-gray-900">{post.title}</span>
+                    </a>
+                })}
+            </div>
+        </nav>
+    }
+}
+
+/// Previous and next post by date, followed by related posts
 #[component]
-pub fn BlogPostMetaTags(post: BlogPost) -> impl IntoView {
+pub fn BlogPostNeighbors(neighbors: PostNeighbors) -> impl IntoView {
+    let PostNeighbors { prev, next, related } = neighbors;
+    let link = |post: BlogPostMeta, label: &'static str| {
+        view! {
+            <a href=format!("/blog/{}", post.slug) class="block p-4 border border-gray-200 rounded-xl hover:border-blue-300 transition">
+                <span class="block text-sm text-gray-500 mb-1">{label}</span>
+                <span class="font-semibold text-gray-900">{post.title}</span>
+            </a>
+        }
+    };
+
+    view! {
+        <div class="mt-16 pt-10 border-t border-gray-200">
+            {(prev.is_some() || next.is_some()).then(|| view! {
+                <nav class="grid sm:grid-cols-2 gap-4 mb-12" aria-label="More posts">
+                    <div>{prev.map(|post| link(post, "← Older post"))}</div>
+                    <div class="sm:text-right">{next.map(|post| link(post, "Newer post →"))}</div>
+                </nav>
+            })}
+            {(!related.is_empty()).then(|| view! {
+                <section>
+                    <h2 class="text-2xl font-bold text-gray-900 mb-6">"Related posts"</h2>
+                    <div class="grid md:grid-cols-3 gap-6">
+                        {related.into_iter().map(|post| view! {
+                            <a href=format!("/blog/{}", post.slug) class="group block p-5 border border-gray-200 rounded-2xl hover:border-blue-300 transition">
+                                <h3 class="font-bold text-gray-900 group-hover:text-blue-600 transition mb-2">
+                                    {post.title}
+                                </h3>
+                                <p class="text-sm text-gray-600 mb-3">{post.description}</p>
+                                <p class="text-xs text-gray-500">
+                                    {post.date.to_string()}
+                                    " · "
+                                    {format!("{} min read", post.reading_time)}
+                                </p>
+                            </a>
+                        }).collect::<Vec<_>>()}
+                    </div>
+                </section>
+            })}
+        </div>
+    }
+}
+
+/// Tag chip linking to the tag's listing page
+#[component]
+pub fn TagLink(tag: String, #[prop(into)] class: String) -> impl IntoView {
+    let href = format!("/blog/tag/{}", slugify(&tag));
+
+    view! {
+        <a href=href class=class>
+            {tag}
+        </a>
+    }
+}
+
+/// Cover image of a post filling its container. Uses the variants from the
+/// image pipeline when there are any; `eager` skips lazy loading for covers
+/// that are visible right away.
+#[component]
+pub fn CoverImage(post: BlogPostMeta, #[prop(optional)] eager: bool) -> impl IntoView {
+    let loading = if eager { "eager" } else { "lazy" };
+    let class = "w-full h-full object-cover";
+
+    match post.cover {
+        Some(image) => view! {
+            <picture>
+                {image.sources.iter().map(|source| view! {
+                    <source type=source.mime.clone() srcset=source.srcset.clone() sizes=image.sizes.clone() />
+                }).collect::<Vec<_>>()}
+                <img
+                    src=image.src.clone()
+                    alt=post.title
+                    width=image.width
+                    height=image.height
+                    loading=loading
+                    decoding="async"
+                    style=image.placeholder_style()
+                    class=class
+                />
+            </picture>
+        }.into_any(),
+        None => view! {
+            <img
+                src=format!("/assets/{}", post.cover_image.unwrap_or_default())
+                alt=post.title
+                loading=loading
+                class=class
+            />
+        }.into_any(),
+    }
+}
+
+/// Summary card linking to a post, used in listings
+#[component]
+pub fn BlogPostCard(post: BlogPostMeta) -> impl IntoView {
+    let href = format!("/blog/{}", post.slug);
+
+    view! {
+        <article class="bg-white border border-gray-200 rounded-2xl overflow-hidden shadow-all text-left">
+            <div class="p-6">
+                <a href=href class="group">
+                    <h2 class="text-2xl font-bold text-gray-900 group-hover:text-blue-600 transition mb-3">
+                        {post.title.clone()}
+                    </h2>
+
+                    <p class="text-gray-600 mb-4">
+                        {post.description.clone()}
+                    </p>
+
+                    <p class="text-sm text-gray-500 mb-4">
+                        {post.date.to_string()}
+                        " · "
+                        {format!("{} min read", post.reading_time)}
+                    </p>
+                </a>
+                {post.cover_image.is_some().then(|| {
+                    view! {
+                        <div class="aspect-video overflow-hidden mb-4">
+                            <CoverImage post=post.clone() />
+                        </div>
+                    }
+                })}
+                <div class="flex flex-wrap gap-2">
+                    {post.tags.iter().map(|tag| {
+                        view! {
+                            <TagLink
+                                tag=tag.clone()
+                                class="px-3 py-1 bg-gray-100 text-gray-700 text-sm rounded-full hover:bg-gray-200 transition"
+                            />
+                        }
+                    }).collect::<Vec<_>>()}
+                </div>
+            </div>
+        </article>
+    }
+}
+
+/// Meta tags component for SEO. `preview` is the token the page was opened
+/// with, which the generated card of an unpublished post needs as well.
+#[component]
+pub fn BlogPostMetaTags(post: BlogPost, preview: Option<String>) -> impl IntoView {
     let og_title = post
         .meta
         .og_title
@@ -60,6 +367,8 @@ pub fn BlogPostMetaTags(post: BlogPost) -> impl IntoView {
         .og_description
         .clone()
         .unwrap_or_else(|| post.meta.description.clone());
+    let config = use_site_config();
+    // Posts without an image of their own get a generated card
     let og_image = post
         .meta
         .og_image
@@ -70,23 +379,46 @@ pub fn BlogPostMetaTags(post: BlogPost) -> impl IntoView {
                 .clone()
                 .map(|img| format!("/assets/{}", img))
         })
-        .unwrap_or_default();
+        .unwrap_or_else(|| match preview {
+            Some(token) => format!("/og/{}.png?preview={}", post.meta.slug, token),
+            None => format!("/og/{}.png", post.meta.slug),
+        });
+    let og_image = config.absolute_url(&og_image);
     let og_type = post
         .meta
         .og_type
         .clone()
         .unwrap_or_else(|| "article".to_string());
-    let site_url = "https://rustbase.site";
-    let canonical_url = format!("{}/blog/{}", site_url, post.meta.slug);
+    let canonical_url = config.url(&format!("/blog/{}", post.meta.slug));
+
+    let mut breadcrumbs = vec![
+        ("Home".to_string(), "/".to_string()),
+        ("Blog".to_string(), "/blog".to_string()),
+    ];
+    if let (Some(series), Some(slug)) = (&post.meta.series, post.meta.series_slug()) {
+        breadcrumbs.push((series.clone(), format!("/blog/series/{}", slug)));
+    }
+    breadcrumbs.push((post.meta.title.clone(), format!("/blog/{}", post.meta.slug)));
 
     view! {
+        <Link rel="canonical" href=canonical_url.clone() />
+        <JsonLd data=structured_data::blog_posting(&post.meta, &og_image, &config) />
+        <JsonLd data=structured_data::breadcrumb_list(&breadcrumbs, &config) />
+
         // OpenGraph meta tags
-        <Meta name="og:title" content=og_title.clone() />
-        <Meta name="og:description" content=og_description.clone() />
-        <Meta name="og:image" content=og_image.clone() />
-        <Meta name="og:type" content=og_type />
-        <Meta name="og:url" content=canonical_url.clone() />
-        <Meta name="og:site_name" content="Rustbase" />
+        <Meta property="og:title" content=og_title.clone() />
+        <Meta property="og:description" content=og_description.clone() />
+        <Meta property="og:image" content=og_image.clone() />
+        <Meta property="og:type" content=og_type />
+        <Meta property="og:url" content=canonical_url />
+        <Meta property="og:site_name" content=config.name.clone() />
+        <Meta property="article:published_time" content=post.meta.published_at().to_rfc3339() />
+        {post.meta.updated.map(|updated| view! {
+            <Meta property="article:modified_time" content=updated.to_rfc3339() />
+        })}
+        {post.meta.tags.iter().map(|tag| view! {
+            <Meta property="article:tag" content=tag.clone() />
+        }).collect::<Vec<_>>()}
 
         // Twitter Card meta tags
         <Meta name="twitter:card" content="summary_large_image" />
@@ -96,23 +428,89 @@ pub fn BlogPostMetaTags(post: BlogPost) -> impl IntoView {
 
         // Standard meta tags
         <Meta name="description" content=post.meta.description.clone() />
-        <Meta name="canonical" content=canonical_url />
-        <Meta name="author" content=post.meta.author.clone() />
-        <Meta name="publish_date" content=post.meta.date.clone() />
+        <Meta name="author" content=post.meta.author_names().join(", ") />
+        <Meta name="publish_date" content=post.meta.date.to_string() />
     }
 }
 
-/// Content component for rendered markdown
+/// Content component for rendered markdown. Shortcode islands inside it
+/// are mounted once it's in the document.
 #[component]
 pub fn BlogPostContent(html_content: String) -> impl IntoView {
+    let content = NodeRef::<leptos::html::Div>::new();
+    Effect::new(move |_| {
+        if let Some(content) = content.get() {
+            mount_islands(&content);
+        }
+    });
+
     view! {
         <div
             class="prose-blog"
+            node_ref=content
             inner_html=html_content
         />
     }
 }
 
+/// Sticky table of contents that highlights the section being read
+#[component]
+pub fn BlogPostToc(entries: Vec<TocEntry>) -> impl IntoView {
+    let active = RwSignal::new(None::<String>);
+
+    // Only runs in the browser: track the last heading scrolled past
+    let ids: Vec<String> = entries.iter().map(|e| e.id.clone()).collect();
+    Effect::new(move |_| {
+        let ids = ids.clone();
+        let update = move || {
+            let current = ids
+                .iter()
+                .take_while(|id| {
+                    document()
+                        .get_element_by_id(id)
+                        .map(|el| el.get_bounding_client_rect().top() < 120.0)
+                        .unwrap_or(false)
+                })
+                .last()
+                .cloned();
+            active.set(current);
+        };
+        update();
+        let handle = window_event_listener(leptos::ev::scroll, move |_| update());
+        on_cleanup(move || handle.remove());
+    });
+
+    view! {
+        <aside class="hidden lg:block">
+            <nav class="sticky top-24 text-left text-sm" aria-label="Table of contents">
+                <h2 class="font-semibold text-gray-900 mb-3">"On this page"</h2>
+                <ul class="space-y-2 border-l border-gray-200">
+                    {entries.into_iter().map(|entry| {
+                        let id = entry.id.clone();
+                        let indent = if entry.level > 2 { "pl-7" } else { "pl-4" };
+                        let class = move || {
+                            let state = if active.get().as_deref() == Some(id.as_str()) {
+                                "text-blue-600 border-blue-600"
+                            } else {
+                                "text-gray-600 border-transparent hover:text-gray-900"
+                            };
+                            format!("block -ml-px border-l-2 transition {} {}", indent, state)
+                        };
+
+                        view! {
+                            <li>
+                                <a href=format!("#{}", entry.id) class=class>
+                                    {entry.title}
+                                </a>
+                            </li>
+                        }
+                    }).collect::<Vec<_>>()}
+                </ul>
+            </nav>
+        </aside>
+    }
+}
+
 /// Loading state component
 #[component]
 pub fn BlogPostLoading() -> impl IntoView {
@@ -123,6 +521,16 @@ pub fn BlogPostLoading() -> impl IntoView {
     }
 }
 
+/// Banner shown above posts opened with a preview token
+#[component]
+pub fn BlogPostPreviewBanner() -> impl IntoView {
+    view! {
+        <div class="mb-8 px-4 py-3 bg-yellow-50 border border-yellow-200 text-yellow-800 rounded-lg">
+            "Preview: this post may not be published yet."
+        </div>
+    }
+}
+
 /// Not found component
 #[component]
 pub fn BlogPostNotFound() -> impl IntoView {
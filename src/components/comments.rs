@@ -0,0 +1,152 @@
+use leptos::prelude::*;
+use leptos::{component, IntoView};
+
+use crate::models::comment::Comment;
+use crate::server_functions::comments::{list_comments, PostComment, MAX_AUTHOR_LEN, MAX_BODY_LEN};
+use crate::server_functions::error_message;
+
+/// Approved comments on a post, with a form to add one
+#[component]
+pub fn Comments(#[prop(into)] slug: String) -> impl IntoView {
+    let comments = Resource::new(
+        {
+            let slug = slug.clone();
+            move || slug.clone()
+        },
+        list_comments,
+    );
+    let thread_slug = slug.clone();
+
+    view! {
+        <section class="mt-16" aria-labelledby="comments">
+            <Suspense fallback=|| ()>
+                {move || comments.get().map(|comments| match comments {
+                    Ok(comments) => {
+                        let count: usize = comments.iter().map(Comment::count).sum();
+                        view! {
+                            <h2 id="comments" class="text-2xl font-bold text-gray-900 mb-6">
+                                {match count {
+                                    0 => "Comments".to_string(),
+                                    1 => "1 comment".to_string(),
+                                    n => format!("{} comments", n),
+                                }}
+                            </h2>
+                            <div class="space-y-6 mb-10">
+                                {comments.into_iter().map(|comment| view! {
+                                    <CommentItem comment=comment slug=thread_slug.clone() />
+                                }).collect::<Vec<_>>()}
+                            </div>
+                        }.into_any()
+                    }
+                    Err(_) => view! {
+                        <h2 id="comments" class="text-2xl font-bold text-gray-900 mb-6">"Comments"</h2>
+                        <p class="text-gray-500 mb-10">"Comments couldn't be loaded."</p>
+                    }.into_any(),
+                })}
+            </Suspense>
+            <CommentForm slug=slug />
+        </section>
+    }
+}
+
+/// A comment and its replies, each with a form to reply
+#[component]
+fn CommentItem(comment: Comment, slug: String) -> impl IntoView {
+    let replying = RwSignal::new(false);
+    let id = comment.id;
+
+    view! {
+        <div class="border-l-2 border-gray-200 pl-4">
+            <p class="text-sm text-gray-500 mb-2">
+                <span class="font-semibold text-gray-900">{comment.author}</span>
+                " · "
+                <time datetime=comment.created_at.to_rfc3339()>
+                    {comment.created_at.format("%B %-d, %Y").to_string()}
+                </time>
+            </p>
+            <div class="prose-blog" inner_html=comment.html />
+            <button
+                type="button"
+                class="mt-2 text-sm font-semibold text-blue-600 hover:text-blue-700"
+                on:click=move |_| replying.update(|replying| *replying = !*replying)
+            >
+                {move || if replying.get() { "Cancel" } else { "Reply" }}
+            </button>
+            {
+                let slug = slug.clone();
+                move || replying.get().then(|| view! {
+                    <div class="mt-4">
+                        <CommentForm slug=slug.clone() parent_id=id />
+                    </div>
+                })
+            }
+            {(!comment.replies.is_empty()).then(|| view! {
+                <div class="mt-6 space-y-6">
+                    {comment.replies.into_iter().map(|reply| view! {
+                        <CommentItem comment=reply slug=slug.clone() />
+                    }).collect::<Vec<_>>()}
+                </div>
+            })}
+        </div>
+    }
+    .into_any()
+}
+
+/// Form that submits a comment, or a reply to `parent_id`, for moderation
+#[component]
+fn CommentForm(
+    #[prop(into)] slug: String,
+    #[prop(optional)] parent_id: Option<i64>,
+) -> impl IntoView {
+    let action = ServerAction::<PostComment>::new();
+    let result = action.value();
+
+    view! {
+        <ActionForm action=action attr:class="space-y-4">
+            <input type="hidden" name="slug" value=slug />
+            {parent_id.map(|id| view! { <input type="hidden" name="parent_id" value=id /> })}
+            // Honeypot: hidden from readers, so only bots fill it in
+            <div class="hidden" aria-hidden="true">
+                <label>
+                    "Website"
+                    <input type="text" name="website" tabindex="-1" autocomplete="off" />
+                </label>
+            </div>
+            <input
+                type="text"
+                name="author"
+                required
+                maxlength=MAX_AUTHOR_LEN
+                placeholder="Your name"
+                class="w-full px-4 py-2 border border-gray-300 rounded-lg focus:outline-none focus:ring-2 focus:ring-blue-500"
+            />
+            <textarea
+                name="body"
+                required
+                rows=if parent_id.is_some() { 3 } else { 5 }
+                maxlength=MAX_BODY_LEN
+                placeholder="Your comment (Markdown works)"
+                class="w-full px-4 py-2 border border-gray-300 rounded-lg focus:outline-none focus:ring-2 focus:ring-blue-500"
+            ></textarea>
+            <div class="flex items-center gap-4">
+                <button
+                    type="submit"
+                    disabled=move || action.pending().get()
+                    class="px-5 py-2 bg-blue-600 text-white font-semibold rounded-lg hover:bg-blue-700 transition disabled:opacity-50"
+                >
+                    {if parent_id.is_some() { "Post reply" } else { "Post comment" }}
+                </button>
+                {move || result.get().map(|result| match result {
+                    Ok(()) => view! {
+                        <p class="text-sm text-green-700">
+                            "Thanks! Your comment will appear once it's approved."
+                        </p>
+                    }.into_any(),
+                    Err(e) => view! {
+                        <p class="text-sm text-red-600">{error_message(&e)}</p>
+                    }.into_any(),
+                })}
+            </div>
+        </ActionForm>
+    }
+}
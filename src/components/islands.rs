@@ -0,0 +1,110 @@
+//! Interactive components mounted into server-rendered markdown.
+//!
+//! Post and page bodies are injected as HTML, so Leptos can't hydrate
+//! components inside them. Shortcodes that want interactivity instead render
+//! an element with `data-island="<name>"` holding plain-HTML fallback content.
+//! Once the content is in the document, [`mount_islands`] reads each island's
+//! fallback and mounts the matching component in its place.
+
+use leptos::prelude::*;
+use wasm_bindgen::JsCast;
+
+/// Mounts the component for every `[data-island]` element below `root`
+pub fn mount_islands(root: &web_sys::Element) {
+    let Ok(islands) = root.query_selector_all("[data-island]") else {
+        return;
+    };
+
+    for i in 0..islands.length() {
+        let Some(island) = islands
+            .item(i)
+            .and_then(|node| node.dyn_into::<web_sys::HtmlElement>().ok())
+        else {
+            continue;
+        };
+        // Only mount once, even if the effect that calls this runs again
+        if island.get_attribute("data-mounted").is_some() {
+            continue;
+        }
+
+        match island.get_attribute("data-island").as_deref() {
+            Some("tabs") => {
+                let panels = tab_panels(&island);
+                island.set_inner_html("");
+                leptos::mount::mount_to(island.clone(), move || view! { <Tabs panels=panels /> })
+                    .forget();
+            }
+            _ => continue,
+        }
+        let _ = island.set_attribute("data-mounted", "");
+    }
+}
+
+/// A panel of the `tabs` shortcode: its label and rendered content
+#[derive(Clone)]
+pub struct TabPanel {
+    pub label: String,
+    pub html: String,
+}
+
+/// Reads the panels out of the fallback markup of the `tabs` shortcode
+fn tab_panels(island: &web_sys::Element) -> Vec<TabPanel> {
+    let mut panels = Vec::new();
+    let Ok(sections) = island.query_selector_all(":scope > .tab-panel") else {
+        return panels;
+    };
+    for i in 0..sections.length() {
+        let Some(section) = sections
+            .item(i)
+            .and_then(|n| n.dyn_into::<web_sys::Element>().ok())
+        else {
+            continue;
+        };
+        let html = section
+            .query_selector(":scope > .tab-content")
+            .ok()
+            .flatten()
+            .map(|content| content.inner_html())
+            .unwrap_or_default();
+        panels.push(TabPanel {
+            label: section.get_attribute("data-label").unwrap_or_default(),
+            html,
+        });
+    }
+    panels
+}
+
+/// Tab bar showing one panel at a time
+#[component]
+pub fn Tabs(panels: Vec<TabPanel>) -> impl IntoView {
+    let selected = RwSignal::new(0);
+    let labels: Vec<(usize, String)> = panels
+        .iter()
+        .map(|panel| panel.label.clone())
+        .enumerate()
+        .collect();
+
+    view! {
+        <div class="tab-bar" role="tablist">
+            {labels.into_iter().map(|(i, label)| view! {
+                <button
+                    type="button"
+                    role="tab"
+                    class="tab-button"
+                    aria-selected=move || (selected.get() == i).to_string()
+                    on:click=move |_| selected.set(i)
+                >
+                    {label}
+                </button>
+            }).collect::<Vec<_>>()}
+        </div>
+        {panels.into_iter().enumerate().map(|(i, panel)| view! {
+            <div
+                class="tab-content"
+                role="tabpanel"
+                hidden=move || selected.get() != i
+                inner_html=panel.html
+            />
+        }).collect::<Vec<_>>()}
+    }
+}
@@ -1,7 +1,10 @@
 pub mod blog;
+pub mod comments;
 pub mod footer;
 pub mod header;
+pub mod islands;
 pub mod layout;
+pub mod structured_data;
 
 pub use footer::Footer;
 pub use header::Header;
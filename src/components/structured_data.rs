@@ -0,0 +1,102 @@
+//! schema.org JSON-LD for search engines.
+//!
+//! The builders return plain JSON values so pages can combine them, and
+//! [`JsonLd`] writes one into the page as a `<script type="application/ld+json">`.
+
+use leptos::prelude::*;
+use leptos::{component, IntoView};
+use serde_json::{json, Value};
+
+use crate::config::SiteConfig;
+use crate::models::author::Author;
+use crate::models::blog::BlogPostMeta;
+
+/// `BlogPosting` for a post, with its authors as `Person`s. `image` is the
+/// absolute URL of the post's social image.
+pub fn blog_posting(meta: &BlogPostMeta, image: &str, config: &SiteConfig) -> Value {
+    let url = config.url(&format!("/blog/{}", meta.slug));
+    let published = meta.published_at().to_rfc3339();
+
+    json!({
+        "@context": "https://schema.org",
+        "@type": "BlogPosting",
+        "headline": meta.title,
+        "description": meta.description,
+        "url": url,
+        "mainEntityOfPage": { "@type": "WebPage", "@id": url },
+        "image": image,
+        "datePublished": published,
+        "dateModified": meta.updated.map(|updated| updated.to_rfc3339()).unwrap_or(published),
+        "author": meta.author_profiles.iter().map(|author| author_ref(author, config)).collect::<Vec<_>>(),
+        "publisher": { "@type": "Organization", "name": config.name, "url": config.base_url },
+        "keywords": meta.tags.join(", "),
+        "wordCount": meta.word_count,
+    })
+}
+
+/// `Person` for an author page, linking the author's profiles through `sameAs`
+pub fn person(author: &Author, config: &SiteConfig) -> Value {
+    let mut person = author_ref(author, config);
+    person["@context"] = json!("https://schema.org");
+    if !author.bio.is_empty() {
+        person["description"] = json!(author.bio);
+    }
+    if let Some(avatar) = author.avatar_url() {
+        person["image"] = json!(config.absolute_url(&avatar));
+    }
+    if !author.links.is_empty() {
+        person["sameAs"] = json!(author.links.iter().map(|link| &link.url).collect::<Vec<_>>());
+    }
+    person
+}
+
+/// `BreadcrumbList` from `(name, path)` pairs, starting at the top
+pub fn breadcrumb_list(crumbs: &[(String, String)], config: &SiteConfig) -> Value {
+    json!({
+        "@context": "https://schema.org",
+        "@type": "BreadcrumbList",
+        "itemListElement": crumbs.iter().enumerate().map(|(i, (name, path))| json!({
+            "@type": "ListItem",
+            "position": i + 1,
+            "name": name,
+            "item": config.url(path),
+        })).collect::<Vec<_>>(),
+    })
+}
+
+/// `WebSite` with a `SearchAction` pointing at `/search`, so search engines
+/// can offer a search box for the site
+pub fn website(config: &SiteConfig) -> Value {
+    json!({
+        "@context": "https://schema.org",
+        "@type": "WebSite",
+        "name": config.name,
+        "description": config.description,
+        "url": config.base_url,
+        "potentialAction": {
+            "@type": "SearchAction",
+            "target": {
+                "@type": "EntryPoint",
+                "urlTemplate": config.url("/search?q={search_term_string}"),
+            },
+            "query-input": "required name=search_term_string",
+        },
+    })
+}
+
+/// Minimal `Person` used wherever an author is referenced
+fn author_ref(author: &Author, config: &SiteConfig) -> Value {
+    json!({
+        "@type": "Person",
+        "name": author.name,
+        "url": config.url(&author.href()),
+    })
+}
+
+/// Writes `data` into the page as a JSON-LD script
+#[component]
+pub fn JsonLd(data: Value) -> impl IntoView {
+    // `</script>` inside a string would end the script early
+    let json = data.to_string().replace("</", "<\\/");
+    view! { <script type="application/ld+json" inner_html=json></script> }
+}
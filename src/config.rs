@@ -0,0 +1,429 @@
+//! Site-wide settings: brand, URLs, navigation and where content lives.
+//!
+//! On the server the config is read once from `site.toml` (see
+//! [`SiteConfig::load`]) and provided to the app as Leptos context. The shell
+//! embeds its public part as JSON in the page head so the client hydrates
+//! with the same values; components read it with [`use_site_config`].
+
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::models::page::{NavPlacement, PageMeta};
+
+/// `id` of the `<script>` tag that carries the config to the client
+pub const SITE_CONFIG_SCRIPT_ID: &str = "site-config";
+
+/// A link in the header, the footer or the social links
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NavItem {
+    pub label: String,
+    pub href: String,
+    /// Rendered as a button rather than a plain link (header only)
+    #[serde(default)]
+    pub primary: bool,
+}
+
+impl NavItem {
+    fn new(label: &str, href: &str) -> Self {
+        Self {
+            label: label.to_string(),
+            href: href.to_string(),
+            primary: false,
+        }
+    }
+
+    /// Whether the link leaves the site and should open in a new tab
+    pub fn is_external(&self) -> bool {
+        self.href.starts_with("http://") || self.href.starts_with("https://")
+    }
+}
+
+/// A titled list of links in the footer
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FooterColumn {
+    pub title: String,
+    pub links: Vec<NavItem>,
+}
+
+/// Whether raw HTML written in content can be served as is
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Trust {
+    /// Rendered HTML is used unchanged
+    Trusted,
+    /// Rendered HTML goes through the sanitizer
+    Untrusted,
+}
+
+/// Tags and attributes the sanitizer allows on top of its built-in
+/// allow-list
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct SanitizeConfig {
+    pub tags: Vec<String>,
+    /// Attributes allowed on every tag
+    pub attributes: Vec<String>,
+}
+
+/// Variants the image pipeline generates for covers and inline images
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ImageConfig {
+    /// Widths in pixels to resize to; widths above the original's are skipped
+    pub widths: Vec<u32>,
+    /// Encoder quality from 0 to 100
+    pub quality: u8,
+    /// `sizes` attribute of the generated `<picture>` sources
+    pub sizes: String,
+}
+
+impl Default for ImageConfig {
+    fn default() -> Self {
+        Self {
+            widths: vec![480, 960, 1440],
+            quality: 75,
+            sizes: "(min-width: 1024px) 768px, 100vw".to_string(),
+        }
+    }
+}
+
+/// Generated Open Graph cards of posts without an image of their own
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct OgConfig {
+    /// Directory rendered cards are cached in
+    pub cache_dir: String,
+    /// Brand color of the cards as `#rrggbb`
+    pub accent: String,
+}
+
+impl Default for OgConfig {
+    fn default() -> Self {
+        Self {
+            cache_dir: "target/og".to_string(),
+            accent: "#2563eb".to_string(),
+        }
+    }
+}
+
+/// What `/robots.txt` asks crawlers to stay out of
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct RobotsConfig {
+    /// Path prefixes listed as `Disallow`. Everything is allowed when empty.
+    pub disallow: Vec<String>,
+}
+
+impl Default for RobotsConfig {
+    fn default() -> Self {
+        Self {
+            disallow: vec![
+                "/api/".to_string(),
+                "/diagnostics/".to_string(),
+                "/admin".to_string(),
+            ],
+        }
+    }
+}
+
+/// Reader comments under posts
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct CommentsConfig {
+    /// Shows comments and the comment form under posts. Needs a server
+    /// built with the `comments` feature.
+    pub enabled: bool,
+    /// SQLite database the comments are stored in
+    #[serde(skip_serializing)]
+    pub db: String,
+    /// Most comments a single client may submit per hour
+    #[serde(skip_serializing)]
+    pub per_hour: u32,
+    /// Addresses of reverse proxies in front of the server. Clients are told
+    /// apart by their connection's address, and `X-Forwarded-For` is only
+    /// believed on connections from one of these. Also used to throttle
+    /// failed admin logins.
+    #[serde(skip_serializing)]
+    pub trusted_proxies: Vec<String>,
+}
+
+impl Default for CommentsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            db: "comments.db".to_string(),
+            per_hour: 5,
+            trusted_proxies: Vec::new(),
+        }
+    }
+}
+
+/// Access to `/diagnostics/content`, which debug builds serve to everyone
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct DiagnosticsConfig {
+    /// Bearer token that opens the diagnostics in release builds. Logged in
+    /// admins can always see them.
+    pub token: Option<String>,
+}
+
+/// The `/admin` area for editing posts
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct AdminConfig {
+    pub username: String,
+    /// Argon2 hash of the password in PHC format, as printed by
+    /// `leptos-bplate hash-password`. The admin area is disabled without one.
+    /// Never sent to the browser.
+    #[serde(skip_serializing)]
+    pub password_hash: Option<String>,
+}
+
+impl Default for AdminConfig {
+    fn default() -> Self {
+        Self {
+            username: "admin".to_string(),
+            password_hash: None,
+        }
+    }
+}
+
+/// Where the server reads content from
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ContentDirs {
+    /// Markdown posts for the `fs` content source
+    pub blog: String,
+    /// Standalone markdown pages such as About or Privacy
+    pub pages: String,
+    /// YAML list of the authors posts can name
+    pub authors: String,
+    /// Overrides the trust level of the content source. Local files are
+    /// trusted by default, databases are not.
+    pub trust: Option<Trust>,
+}
+
+impl Default for ContentDirs {
+    fn default() -> Self {
+        Self {
+            blog: "content/blog".to_string(),
+            pages: "content/pages".to_string(),
+            authors: "content/authors.yaml".to_string(),
+            trust: None,
+        }
+    }
+}
+
+/// Everything that differs between deployments of the site. Every field is
+/// optional in `site.toml` and falls back to the values below.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct SiteConfig {
+    /// Brand shown in the header, footer, feeds and social previews
+    pub name: String,
+    /// One-line description used by the footer, the blog listing and the feeds
+    pub description: String,
+    /// Absolute URL the site is served from, without a trailing slash
+    pub base_url: String,
+    /// Author id of posts that don't name one in their frontmatter
+    #[serde(skip_serializing)]
+    pub default_author: String,
+    /// Links in the header, in order
+    pub nav: Vec<NavItem>,
+    pub footer_columns: Vec<FooterColumn>,
+    /// Small links next to the copyright notice
+    pub footer_links: Vec<NavItem>,
+    pub social_links: Vec<NavItem>,
+    pub copyright: String,
+    // Server-only settings below are left out of the JSON sent to the
+    // browser, which falls back to their defaults
+    #[serde(skip_serializing)]
+    pub content: ContentDirs,
+    /// Allow-list additions for sanitizing untrusted content
+    #[serde(skip_serializing)]
+    pub sanitize: SanitizeConfig,
+    #[serde(skip_serializing)]
+    pub images: ImageConfig,
+    #[serde(skip_serializing)]
+    pub og: OgConfig,
+    #[serde(skip_serializing)]
+    pub robots: RobotsConfig,
+    pub comments: CommentsConfig,
+    #[serde(skip_serializing)]
+    pub diagnostics: DiagnosticsConfig,
+    #[serde(skip_serializing)]
+    pub admin: AdminConfig,
+}
+
+impl Default for SiteConfig {
+    fn default() -> Self {
+        Self {
+            name: "Rustbase".to_string(),
+            description: "Thoughts, tutorials, and updates from our team.".to_string(),
+            base_url: "https://rustbase.site".to_string(),
+            default_author: "leptos-team".to_string(),
+            nav: vec![
+                NavItem::new("Features", "/#features"),
+                NavItem::new("How It Works", "/#how-it-works"),
+                NavItem::new("Blog", "/blog"),
+                NavItem::new("Search", "/search"),
+                NavItem {
+                    primary: true,
+                    ..NavItem::new(
+                        "Get Started",
+                        "https://github.com/frontmesh/leptos-cloudflare-workers",
+                    )
+                },
+            ],
+            footer_columns: vec![
+                FooterColumn {
+                    title: "Product".to_string(),
+                    links: vec![
+                        NavItem::new("Features", "/#features"),
+                        NavItem::new("How It Works", "/#how-it-works"),
+                    ],
+                },
+                FooterColumn {
+                    title: "Resources".to_string(),
+                    links: vec![
+                        NavItem::new("Leptos Docs", "https://book.leptos.dev/"),
+                        NavItem::new("Workers Docs", "https://developers.cloudflare.com/workers/"),
+                    ],
+                },
+                FooterColumn {
+                    title: "Company".to_string(),
+                    links: vec![
+                        NavItem::new("About", "/about"),
+                        NavItem::new("Blog", "/blog"),
+                        NavItem::new("Contact", "/contact"),
+                    ],
+                },
+            ],
+            footer_links: Vec::new(),
+            social_links: vec![NavItem::new(
+                "GitHub",
+                "https://github.com/frontmesh/leptos-tailwind-docker",
+            )],
+            copyright: "© 2025 Leptos. All rights reserved.".to_string(),
+            content: ContentDirs::default(),
+            sanitize: SanitizeConfig::default(),
+            images: ImageConfig::default(),
+            og: OgConfig::default(),
+            robots: RobotsConfig::default(),
+            comments: CommentsConfig::default(),
+            diagnostics: DiagnosticsConfig::default(),
+            admin: AdminConfig::default(),
+        }
+    }
+}
+
+impl SiteConfig {
+    /// Absolute URL of a root-relative `path`
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    /// `url` made absolute if it's a root-relative path, as it is otherwise
+    pub fn absolute_url(&self, url: &str) -> String {
+        if url.starts_with('/') && !url.starts_with("//") {
+            self.url(url)
+        } else {
+            url.to_string()
+        }
+    }
+
+    /// Adds links to the pages that ask for a place in the navigation.
+    /// Header links go before the primary call to action, footer links next
+    /// to the copyright notice.
+    pub fn add_page_links(&mut self, pages: &[PageMeta]) {
+        let mut pages: Vec<&PageMeta> = pages.iter().filter(|page| page.nav.is_some()).collect();
+        pages.sort_by(|a, b| a.nav_order.cmp(&b.nav_order).then_with(|| a.title.cmp(&b.title)));
+
+        for page in pages {
+            let link = NavItem::new(
+                page.nav_label.as_deref().unwrap_or(&page.title),
+                &page.href(),
+            );
+            match page.nav {
+                Some(NavPlacement::Header) => {
+                    let at = self
+                        .nav
+                        .iter()
+                        .position(|item| item.primary)
+                        .unwrap_or(self.nav.len());
+                    self.nav.insert(at, link);
+                }
+                Some(NavPlacement::Footer) => self.footer_links.push(link),
+                None => {}
+            }
+        }
+    }
+
+    /// JSON for the config `<script>` tag, safe to inline into HTML. Only
+    /// holds what the components need; paths, credentials and the other
+    /// server-only settings are skipped.
+    pub fn to_script_json(&self) -> String {
+        serde_json::to_string(self)
+            .unwrap_or_default()
+            .replace("</", "<\\/")
+    }
+
+    /// Reads the config the server embedded in the page, falling back to the
+    /// defaults if it's missing or malformed
+    #[cfg(feature = "hydrate")]
+    pub fn from_document() -> Self {
+        document()
+            .get_element_by_id(SITE_CONFIG_SCRIPT_ID)
+            .and_then(|script| script.text_content())
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(feature = "ssr")]
+impl SiteConfig {
+    /// Reads `SITE_CONFIG` (defaults to `site.toml`, which may be missing)
+    /// and applies the environment overrides on top:
+    ///
+    /// * `SITE_NAME` - [`name`](Self::name)
+    /// * `SITE_URL` - [`base_url`](Self::base_url)
+    /// * `CONTENT_DIR` - [`content.blog`](ContentDirs::blog)
+    pub fn load() -> std::io::Result<Self> {
+        let path = std::env::var("SITE_CONFIG").unwrap_or_else(|_| "site.toml".to_string());
+        let mut config: Self = match std::fs::read_to_string(&path) {
+            Ok(text) => toml::from_str(&text).map_err(|e| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!("invalid site config {}: {}", path, e),
+                )
+            })?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Self::default(),
+            Err(e) => return Err(e),
+        };
+
+        if let Ok(name) = std::env::var("SITE_NAME") {
+            config.name = name;
+        }
+        if let Ok(url) = std::env::var("SITE_URL") {
+            config.base_url = url;
+        }
+        if let Ok(dir) = std::env::var("CONTENT_DIR") {
+            config.content.blog = dir;
+        }
+        config.base_url = config.base_url.trim_end_matches('/').to_string();
+        if config.comments.enabled && !cfg!(feature = "comments") {
+            eprintln!(
+                "Comments are enabled in {} but the server was built without the `comments` feature",
+                path
+            );
+            config.comments.enabled = false;
+        }
+
+        Ok(config)
+    }
+}
+
+/// The [`SiteConfig`] provided to the app, or the defaults outside of it
+pub fn use_site_config() -> SiteConfig {
+    use_context::<SiteConfig>().unwrap_or_default()
+}
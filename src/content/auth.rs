@@ -0,0 +1,148 @@
+//! Login for the admin area.
+//!
+//! The password is checked against the argon2 hash in `[admin]` of the site
+//! config. A successful login sets a session cookie holding a
+//! [token](crate::content::token) signed for the username. The signing key is
+//! generated when the server starts, so restarting it ends every session.
+//!
+//! Failed logins are throttled per client, so the password can't be guessed
+//! quickly from one address. There is no limit across all clients, which
+//! would let anyone lock the admin out.
+
+use std::sync::LazyLock;
+
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use chrono::{DateTime, Duration, Utc};
+
+use crate::config::AdminConfig;
+use crate::content::rate_limit::RateLimiter;
+use crate::content::token::{sign_token, verify_token};
+
+/// Name of the session cookie
+pub const SESSION_COOKIE: &str = "admin_session";
+/// How long a login lasts
+pub const SESSION_HOURS: i64 = 12;
+
+/// Failed logins a single client may make per [`LOGIN_WINDOW_MINUTES`]
+pub const LOGIN_FAILURES_PER_CLIENT: usize = 5;
+pub const LOGIN_WINDOW_MINUTES: i64 = 15;
+
+static CLIENT_FAILURES: LazyLock<RateLimiter> = LazyLock::new(|| {
+    RateLimiter::new(
+        LOGIN_FAILURES_PER_CLIENT,
+        Duration::minutes(LOGIN_WINDOW_MINUTES),
+    )
+});
+
+static SESSION_KEY: LazyLock<[u8; 32]> = LazyLock::new(|| {
+    let mut key = [0; 32];
+    OsRng.fill_bytes(&mut key);
+    key
+});
+
+/// Argon2 hash of `password` in PHC format, for `[admin] password_hash`
+pub fn hash_password(password: &str) -> Result<String, argon2::password_hash::Error> {
+    let salt = SaltString::generate(&mut OsRng);
+    Ok(Argon2::default()
+        .hash_password(password.as_bytes(), &salt)?
+        .to_string())
+}
+
+/// Whether `username` and `password` match the admin credentials. Always
+/// `false` while no password hash is configured.
+pub fn check_credentials(config: &AdminConfig, username: &str, password: &str) -> bool {
+    let Some(hash) = config.password_hash.as_deref() else {
+        return false;
+    };
+    let hash = match PasswordHash::new(hash) {
+        Ok(hash) => hash,
+        Err(e) => {
+            eprintln!("Invalid [admin] password_hash: {}", e);
+            return false;
+        }
+    };
+    // The password is checked even for a wrong username, so both fail
+    // equally slowly
+    let password_ok = Argon2::default()
+        .verify_password(password.as_bytes(), &hash)
+        .is_ok();
+    password_ok && username == config.username
+}
+
+/// Whether logins from `client` are refused at `now` because of too many
+/// recent failures
+pub fn login_throttled(client: &str, now: DateTime<Utc>) -> bool {
+    CLIENT_FAILURES.is_limited(client, now)
+}
+
+/// Counts a failed login of `client` at `now` towards [`login_throttled`]
+pub fn record_failed_login(client: &str, now: DateTime<Utc>) {
+    CLIENT_FAILURES.record(client, now);
+}
+
+/// Session cookie value for `username`, valid for [`SESSION_HOURS`] from `now`
+pub fn sign_session(username: &str, now: DateTime<Utc>) -> String {
+    sign_token(
+        SESSION_KEY.as_slice(),
+        username,
+        now + Duration::hours(SESSION_HOURS),
+    )
+}
+
+/// Checks that `token` was signed for `username` and hasn't expired at `now`
+pub fn verify_session(username: &str, token: &str, now: DateTime<Utc>) -> bool {
+    verify_token(SESSION_KEY.as_slice(), username, token, now)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn binds_session_to_username_for_session_hours() {
+        let token = sign_session("admin", now());
+        assert!(verify_session("admin", &token, now()));
+        assert!(!verify_session("root", &token, now()));
+
+        let expired = now() + Duration::hours(SESSION_HOURS) + Duration::seconds(1);
+        assert!(!verify_session("admin", &token, expired));
+    }
+
+    #[test]
+    fn checks_credentials() {
+        let config = AdminConfig {
+            username: "admin".to_string(),
+            password_hash: Some(hash_password("hunter22").unwrap()),
+        };
+        assert!(check_credentials(&config, "admin", "hunter22"));
+        assert!(!check_credentials(&config, "admin", "hunter2"));
+        assert!(!check_credentials(&config, "root", "hunter22"));
+
+        let disabled = AdminConfig {
+            password_hash: None,
+            ..config
+        };
+        assert!(!check_credentials(&disabled, "admin", "hunter22"));
+    }
+
+    #[test]
+    fn throttles_repeated_failures_per_client() {
+        for _ in 0..LOGIN_FAILURES_PER_CLIENT {
+            assert!(!login_throttled("throttled-client", now()));
+            record_failed_login("throttled-client", now());
+        }
+        assert!(login_throttled("throttled-client", now()));
+        assert!(!login_throttled("other-client", now()));
+
+        let later = now() + Duration::minutes(LOGIN_WINDOW_MINUTES + 1);
+        assert!(!login_throttled("throttled-client", later));
+    }
+}
@@ -0,0 +1,83 @@
+//! Author registry read from `content/authors.yaml`.
+//!
+//! Posts name their authors by id; the store resolves those ids to full
+//! profiles while parsing. Without a registry file authors stay free-form
+//! names, so existing sites keep working.
+
+use std::io;
+
+use crate::config::SiteConfig;
+use crate::content::error::ContentError;
+use crate::models::author::Author;
+
+#[cfg(feature = "embed")]
+static EMBEDDED_AUTHORS: &str =
+    include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/content/authors.yaml"));
+
+#[derive(Debug, Default)]
+pub struct AuthorRegistry {
+    authors: Vec<Author>,
+}
+
+impl AuthorRegistry {
+    /// Parses the YAML list of authors, rejecting duplicate ids
+    pub fn parse(yaml: &str) -> io::Result<Self> {
+        let authors: Vec<Author> = serde_yaml::from_str(yaml).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid author registry: {}", e),
+            )
+        })?;
+
+        for (i, author) in authors.iter().enumerate() {
+            if authors[..i].iter().any(|a| a.id == author.id) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "author `{}` is listed twice in the author registry",
+                        author.id
+                    ),
+                ));
+            }
+        }
+
+        Ok(Self { authors })
+    }
+
+    /// Reads the registry at the `authors` path of the site config, or the
+    /// embedded one with `CONTENT_SOURCE=embedded`. A missing file means an
+    /// empty registry.
+    pub fn from_config(config: &SiteConfig) -> io::Result<Self> {
+        #[cfg(feature = "embed")]
+        if std::env::var("CONTENT_SOURCE").as_deref() == Ok("embedded") {
+            return Self::parse(EMBEDDED_AUTHORS);
+        }
+
+        match std::fs::read_to_string(&config.content.authors) {
+            Ok(yaml) => Self::parse(&yaml),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Looks up an author by id
+    pub fn get(&self, id: &str) -> Option<&Author> {
+        self.authors.iter().find(|a| a.id == id)
+    }
+
+    /// All registered authors, in file order
+    pub fn authors(&self) -> &[Author] {
+        &self.authors
+    }
+
+    /// The profile for an id from the frontmatter. With an empty registry
+    /// every id is accepted as a display name.
+    pub fn resolve(&self, id: &str) -> Result<Author, ContentError> {
+        if self.authors.is_empty() {
+            return Ok(Author::named(id));
+        }
+        self.get(id)
+            .cloned()
+            .ok_or_else(|| ContentError::UnknownAuthor { id: id.to_string() })
+    }
+}
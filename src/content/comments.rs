@@ -0,0 +1,198 @@
+//! Reader comments, stored in SQLite.
+//!
+//! Comments are written to a `comments` table as they are submitted and stay
+//! hidden until they are approved from the moderation queue
+//! (`leptos-bplate comments`). Bodies are markdown, rendered with a strict
+//! allow-list of inline formatting, links, code and lists when they are
+//! submitted, so listing comments only reads stored HTML.
+
+use std::{collections::HashMap, io, path::Path, sync::Mutex};
+
+use chrono::{DateTime, Utc};
+use rusqlite::OptionalExtension;
+
+use crate::content::markdown::render_comment;
+use crate::models::comment::Comment;
+
+/// A comment waiting in the moderation queue
+#[derive(Debug, Clone)]
+pub struct PendingComment {
+    pub id: i64,
+    pub slug: String,
+    pub parent_id: Option<i64>,
+    pub author: String,
+    /// Markdown as submitted
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Comments of every post, see the [module docs](self)
+pub struct CommentStore {
+    conn: Mutex<rusqlite::Connection>,
+}
+
+impl CommentStore {
+    /// Opens the database at `path`, creating the table if it doesn't exist
+    /// yet
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let conn = rusqlite::Connection::open(path).map_err(io::Error::other)?;
+        conn.execute_batch(
+            "PRAGMA foreign_keys = ON;
+            CREATE TABLE IF NOT EXISTS comments (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                slug TEXT NOT NULL,
+                parent_id INTEGER REFERENCES comments (id) ON DELETE CASCADE,
+                author TEXT NOT NULL,
+                body TEXT NOT NULL,
+                html TEXT NOT NULL,
+                approved INTEGER NOT NULL DEFAULT 0,
+                client TEXT NOT NULL,
+                created_at INTEGER NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS comments_by_slug ON comments (slug, approved);
+            CREATE INDEX IF NOT EXISTS comments_by_client ON comments (client, created_at);",
+        )
+        .map_err(io::Error::other)?;
+
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// Approved comments on the post with `slug` as threads, oldest first.
+    /// Replies are only shown while the comment they answer is approved.
+    pub fn approved(&self, slug: &str) -> io::Result<Vec<Comment>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, parent_id, author, html, created_at FROM comments
+                WHERE slug = ?1 AND approved = 1 ORDER BY created_at, id",
+            )
+            .map_err(io::Error::other)?;
+        let rows = stmt
+            .query_map([slug], |row| {
+                Ok((
+                    row.get::<_, Option<i64>>(1)?,
+                    Comment {
+                        id: row.get(0)?,
+                        author: row.get(2)?,
+                        html: row.get(3)?,
+                        created_at: timestamp(row.get(4)?),
+                        replies: Vec::new(),
+                    },
+                ))
+            })
+            .map_err(io::Error::other)?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(io::Error::other)?;
+
+        let mut children: HashMap<Option<i64>, Vec<Comment>> = HashMap::new();
+        for (parent_id, comment) in rows {
+            children.entry(parent_id).or_default().push(comment);
+        }
+        Ok(thread(None, &mut children))
+    }
+
+    /// Whether the comment `id` is approved and belongs to the post with `slug`
+    pub fn is_approved(&self, slug: &str, id: i64) -> io::Result<bool> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT 1 FROM comments WHERE id = ?1 AND slug = ?2 AND approved = 1",
+            rusqlite::params![id, slug],
+            |_| Ok(()),
+        )
+        .optional()
+        .map(|found| found.is_some())
+        .map_err(io::Error::other)
+    }
+
+    /// How many comments `client` submitted after `since`, approved or not
+    pub fn submitted_since(&self, client: &str, since: DateTime<Utc>) -> io::Result<u32> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT COUNT(*) FROM comments WHERE client = ?1 AND created_at > ?2",
+            rusqlite::params![client, since.timestamp()],
+            |row| row.get(0),
+        )
+        .map_err(io::Error::other)
+    }
+
+    /// Adds a comment to the moderation queue and returns its id. `client`
+    /// identifies the submitter for rate limiting.
+    pub fn add(
+        &self,
+        slug: &str,
+        parent_id: Option<i64>,
+        author: &str,
+        body: &str,
+        client: &str,
+        now: DateTime<Utc>,
+    ) -> io::Result<i64> {
+        let html = render_comment(body);
+
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO comments (slug, parent_id, author, body, html, client, created_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            rusqlite::params![slug, parent_id, author, body, html, client, now.timestamp()],
+        )
+        .map_err(io::Error::other)?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// Comments waiting for approval, oldest first
+    pub fn pending(&self) -> io::Result<Vec<PendingComment>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, slug, parent_id, author, body, created_at FROM comments
+                WHERE approved = 0 ORDER BY created_at, id",
+            )
+            .map_err(io::Error::other)?;
+        let pending = stmt
+            .query_map((), |row| {
+                Ok(PendingComment {
+                    id: row.get(0)?,
+                    slug: row.get(1)?,
+                    parent_id: row.get(2)?,
+                    author: row.get(3)?,
+                    body: row.get(4)?,
+                    created_at: timestamp(row.get(5)?),
+                })
+            })
+            .map_err(io::Error::other)?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(io::Error::other)?;
+        Ok(pending)
+    }
+
+    /// Publishes the comment `id`. `false` if there is no such comment.
+    pub fn approve(&self, id: i64) -> io::Result<bool> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("UPDATE comments SET approved = 1 WHERE id = ?1", [id])
+            .map(|changed| changed > 0)
+            .map_err(io::Error::other)
+    }
+
+    /// Deletes the comment `id` and every reply to it. `false` if there is
+    /// no such comment.
+    pub fn delete(&self, id: i64) -> io::Result<bool> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM comments WHERE id = ?1", [id])
+            .map(|changed| changed > 0)
+            .map_err(io::Error::other)
+    }
+}
+
+/// Takes the replies to `parent` out of `children`, each with its own replies
+fn thread(parent: Option<i64>, children: &mut HashMap<Option<i64>, Vec<Comment>>) -> Vec<Comment> {
+    let mut comments = children.remove(&parent).unwrap_or_default();
+    for comment in &mut comments {
+        comment.replies = thread(Some(comment.id), children);
+    }
+    comments
+}
+
+fn timestamp(secs: i64) -> DateTime<Utc> {
+    DateTime::from_timestamp(secs, 0).unwrap_or_default()
+}
@@ -0,0 +1,89 @@
+use std::fmt;
+
+use serde::Serialize;
+
+/// Why a content entry could not be turned into a [`BlogPost`](crate::models::blog::BlogPost)
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ContentError {
+    /// The entry could not be read from its source
+    Io { message: String },
+    /// The document has no `---` frontmatter block
+    MissingFrontmatter,
+    /// The frontmatter block is not valid YAML
+    InvalidYaml { message: String },
+    /// The frontmatter is valid YAML but doesn't match `BlogPostMeta`
+    InvalidFrontmatter { message: String },
+    /// A date field is not `YYYY-MM-DD` or RFC 3339
+    BadDate { field: String, value: String },
+    /// Another entry already provides the same slug
+    DuplicateSlug { slug: String, existing: String },
+    /// `author` or `authors` names an id that isn't in the author registry
+    UnknownAuthor { id: String },
+    /// A shortcode that isn't in the registry
+    UnknownShortcode { name: String },
+    /// A known shortcode with bad syntax or attributes
+    InvalidShortcode { name: String, reason: String },
+}
+
+impl fmt::Display for ContentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ContentError::Io { message } => write!(f, "could not read post: {}", message),
+            ContentError::MissingFrontmatter => write!(f, "no frontmatter found in post"),
+            ContentError::InvalidYaml { message } => {
+                write!(f, "frontmatter is not valid YAML: {}", message)
+            }
+            ContentError::InvalidFrontmatter { message } => {
+                write!(f, "invalid frontmatter: {}", message)
+            }
+            ContentError::BadDate { field, value } => {
+                write!(f, "`{}` is not a valid date: {:?}", field, value)
+            }
+            ContentError::DuplicateSlug { slug, existing } => {
+                write!(f, "slug `{}` is already used by {}", slug, existing)
+            }
+            ContentError::UnknownAuthor { id } => {
+                write!(f, "author `{}` is not in the author registry", id)
+            }
+            ContentError::UnknownShortcode { name } => write!(f, "unknown shortcode `{}`", name),
+            ContentError::InvalidShortcode { name, reason } if name.is_empty() => {
+                write!(f, "invalid shortcode: {}", reason)
+            }
+            ContentError::InvalidShortcode { name, reason } => {
+                write!(f, "invalid `{}` shortcode: {}", name, reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ContentError {}
+
+impl From<std::io::Error> for ContentError {
+    fn from(e: std::io::Error) -> Self {
+        ContentError::Io {
+            message: e.to_string(),
+        }
+    }
+}
+
+/// A [`ContentError`] together with the source entry it came from
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ContentDiagnostic {
+    /// Id of the entry in its [`ContentSource`](crate::content::ContentSource)
+    pub entry: String,
+    #[serde(flatten)]
+    pub error: ContentError,
+    /// Human readable version of `error`
+    pub message: String,
+}
+
+impl ContentDiagnostic {
+    pub fn new(entry: &str, error: ContentError) -> Self {
+        Self {
+            entry: entry.to_string(),
+            message: error.to_string(),
+            error,
+        }
+    }
+}
@@ -0,0 +1,219 @@
+//! RSS 2.0, Atom and JSON Feed documents built from the listed posts.
+
+use chrono::{DateTime, Utc};
+use serde_json::json;
+
+use crate::config::SiteConfig;
+use crate::content::html::rewrite_tags;
+use crate::content::xml_escape;
+use crate::models::blog::BlogPost;
+
+/// Site-wide details every feed needs
+pub struct FeedInfo {
+    /// Absolute base URL without a trailing slash, e.g. `https://example.com`
+    pub site_url: String,
+    pub title: String,
+    pub description: String,
+    /// Path of the feed document itself, e.g. `/feed.xml`
+    pub self_path: String,
+}
+
+impl FeedInfo {
+    /// Feed info for the whole blog, titled after the site
+    pub fn new(config: &SiteConfig, self_path: &str) -> Self {
+        Self {
+            site_url: config.base_url.clone(),
+            title: config.name.clone(),
+            description: config.description.clone(),
+            self_path: self_path.to_string(),
+        }
+    }
+
+    /// Narrows the feed down to a single tag
+    pub fn for_tag(mut self, tag: &str) -> Self {
+        self.title = format!("{} - {}", self.title, tag);
+        self
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.site_url, path)
+    }
+
+    /// `url` resolved against the site if it is root-relative. Protocol
+    /// relative `//host/...` URLs are left alone.
+    fn absolute_url(&self, url: &str) -> String {
+        match url.starts_with('/') && !url.starts_with("//") {
+            true => self.url(url),
+            false => url.to_string(),
+        }
+    }
+
+    fn post_url(&self, post: &BlogPost) -> String {
+        self.url(&format!("/blog/{}", post.meta.slug))
+    }
+
+    /// Rendered post HTML with root-relative `href`, `src` and `srcset`
+    /// attributes made absolute, since feed readers have no base URL to
+    /// resolve them against
+    fn content(&self, post: &BlogPost) -> String {
+        rewrite_tags(&post.content, |tag| {
+            for attribute in ["href", "src", "srcset"] {
+                let Some(value) = tag.attribute(attribute) else {
+                    continue;
+                };
+                let absolute = match attribute {
+                    // A comma separated list of `<url> <width>`
+                    "srcset" => value
+                        .split(',')
+                        .map(|candidate| self.absolute_url(candidate.trim_start()))
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    _ => self.absolute_url(value),
+                };
+                if absolute != value {
+                    tag.set_attribute(attribute, &absolute);
+                }
+            }
+        })
+    }
+}
+
+fn cdata(text: &str) -> String {
+    format!("<![CDATA[{}]]>", text.replace("]]>", "]]]]><![CDATA[>"))
+}
+
+fn last_updated(posts: &[BlogPost]) -> DateTime<Utc> {
+    posts
+        .iter()
+        .map(|p| p.meta.updated.unwrap_or_else(|| p.meta.published_at()))
+        .max()
+        .unwrap_or_else(Utc::now)
+}
+
+/// Newest posts first, which is what feed readers expect
+fn newest_first(posts: &[BlogPost]) -> Vec<&BlogPost> {
+    let mut posts: Vec<&BlogPost> = posts.iter().collect();
+    posts.sort_by_key(|p| std::cmp::Reverse(p.meta.published_at()));
+    posts
+}
+
+/// RSS 2.0 document
+pub fn rss(info: &FeedInfo, posts: &[BlogPost]) -> String {
+    let mut xml = String::new();
+    xml.push_str(r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    xml.push_str(r#"<rss version="2.0" xmlns:atom="http://www.w3.org/2005/Atom" xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:content="http://purl.org/rss/1.0/modules/content/">"#);
+    xml.push_str("<channel>");
+    xml.push_str(&format!("<title>{}</title>", xml_escape(&info.title)));
+    xml.push_str(&format!("<link>{}</link>", xml_escape(&info.url("/blog"))));
+    xml.push_str(&format!("<description>{}</description>", xml_escape(&info.description)));
+    xml.push_str(&format!(
+        r#"<atom:link href="{}" rel="self" type="application/rss+xml"/>"#,
+        xml_escape(&info.url(&info.self_path))
+    ));
+    xml.push_str(&format!("<lastBuildDate>{}</lastBuildDate>", last_updated(posts).to_rfc2822()));
+
+    for post in newest_first(posts) {
+        let url = info.post_url(post);
+        xml.push_str("<item>");
+        xml.push_str(&format!("<title>{}</title>", xml_escape(&post.meta.title)));
+        xml.push_str(&format!("<link>{}</link>", xml_escape(&url)));
+        xml.push_str(&format!(r#"<guid isPermaLink="true">{}</guid>"#, xml_escape(&url)));
+        xml.push_str(&format!("<description>{}</description>", xml_escape(&post.meta.description)));
+        xml.push_str(&format!("<content:encoded>{}</content:encoded>", cdata(&info.content(post))));
+        for name in post.meta.author_names() {
+            xml.push_str(&format!("<dc:creator>{}</dc:creator>", xml_escape(&name)));
+        }
+        xml.push_str(&format!("<pubDate>{}</pubDate>", post.meta.published_at().to_rfc2822()));
+        for tag in &post.meta.tags {
+            xml.push_str(&format!("<category>{}</category>", xml_escape(tag)));
+        }
+        xml.push_str("</item>");
+    }
+
+    xml.push_str("</channel></rss>");
+    xml
+}
+
+/// Atom 1.0 document
+pub fn atom(info: &FeedInfo, posts: &[BlogPost]) -> String {
+    let mut xml = String::new();
+    xml.push_str(r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    xml.push_str(r#"<feed xmlns="http://www.w3.org/2005/Atom">"#);
+    xml.push_str(&format!("<id>{}</id>", xml_escape(&info.url(&info.self_path))));
+    xml.push_str(&format!("<title>{}</title>", xml_escape(&info.title)));
+    xml.push_str(&format!("<subtitle>{}</subtitle>", xml_escape(&info.description)));
+    xml.push_str(&format!(r#"<link href="{}"/>"#, xml_escape(&info.url("/blog"))));
+    xml.push_str(&format!(
+        r#"<link href="{}" rel="self" type="application/atom+xml"/>"#,
+        xml_escape(&info.url(&info.self_path))
+    ));
+    xml.push_str(&format!("<updated>{}</updated>", last_updated(posts).to_rfc3339()));
+
+    for post in newest_first(posts) {
+        let url = info.post_url(post);
+        let updated = post.meta.updated.unwrap_or_else(|| post.meta.published_at());
+        xml.push_str("<entry>");
+        xml.push_str(&format!("<id>{}</id>", xml_escape(&url)));
+        xml.push_str(&format!("<title>{}</title>", xml_escape(&post.meta.title)));
+        xml.push_str(&format!(r#"<link href="{}"/>"#, xml_escape(&url)));
+        xml.push_str(&format!("<published>{}</published>", post.meta.published_at().to_rfc3339()));
+        xml.push_str(&format!("<updated>{}</updated>", updated.to_rfc3339()));
+        for name in post.meta.author_names() {
+            xml.push_str(&format!("<author><name>{}</name></author>", xml_escape(&name)));
+        }
+        xml.push_str(&format!("<summary>{}</summary>", xml_escape(&post.meta.description)));
+        xml.push_str(&format!(r#"<content type="html">{}</content>"#, xml_escape(&info.content(post))));
+        for tag in &post.meta.tags {
+            xml.push_str(&format!(r#"<category term="{}"/>"#, xml_escape(tag)));
+        }
+        xml.push_str("</entry>");
+    }
+
+    xml.push_str("</feed>");
+    xml
+}
+
+/// JSON Feed 1.1 document
+pub fn json_feed(info: &FeedInfo, posts: &[BlogPost]) -> serde_json::Value {
+    let items: Vec<serde_json::Value> = newest_first(posts)
+        .into_iter()
+        .map(|post| {
+            let url = info.post_url(post);
+            let authors: Vec<serde_json::Value> = if post.meta.author_profiles.is_empty() {
+                vec![json!({ "name": post.meta.author })]
+            } else {
+                post.meta
+                    .author_profiles
+                    .iter()
+                    .map(|author| json!({ "name": author.name, "url": info.url(&author.href()) }))
+                    .collect()
+            };
+            let mut item = json!({
+                "id": url,
+                "url": url,
+                "title": post.meta.title,
+                "summary": post.meta.description,
+                "content_html": info.content(post),
+                "date_published": post.meta.published_at().to_rfc3339(),
+                "authors": authors,
+                "tags": post.meta.tags,
+            });
+            if let Some(updated) = post.meta.updated {
+                item["date_modified"] = json!(updated.to_rfc3339());
+            }
+            if let Some(image) = &post.meta.cover_image {
+                item["image"] = json!(info.url(&format!("/assets/{}", image)));
+            }
+            item
+        })
+        .collect();
+
+    json!({
+        "version": "https://jsonfeed.org/version/1.1",
+        "title": info.title,
+        "description": info.description,
+        "home_page_url": info.url("/blog"),
+        "feed_url": info.url(&info.self_path),
+        "items": items,
+    })
+}
@@ -0,0 +1,235 @@
+//! Server-side syntax highlighting for fenced code blocks.
+//!
+//! Tokens are emitted as `<span class="hl-...">` with one class per scope
+//! atom (`hl-keyword hl-control`), so the colors live in `style/tailwind.css`
+//! rather than in the HTML. The fence info string picks the language and can
+//! also mark lines and give the block a caption:
+//!
+//! ````text
+//! ```rust {3-5,8} title="src/main.rs"
+//! ````
+
+use std::ops::RangeInclusive;
+use std::sync::LazyLock;
+
+use pulldown_cmark::{CodeBlockKind, CowStr, Event, Tag, TagEnd};
+use syntect::html::{ClassStyle, ClassedHTMLGenerator};
+use syntect::parsing::SyntaxSet;
+use syntect::util::LinesWithEndings;
+
+use crate::content::xml_escape;
+
+static SYNTAXES: LazyLock<SyntaxSet> = LazyLock::new(SyntaxSet::load_defaults_newlines);
+
+const CLASS_STYLE: ClassStyle = ClassStyle::SpacedPrefixed { prefix: "hl-" };
+
+/// Options parsed from a fence info string
+#[derive(Debug, Default, PartialEq)]
+pub struct CodeFence {
+    /// Language token, e.g. `rust`
+    pub lang: Option<String>,
+    /// 1-based line ranges to highlight
+    pub highlight: Vec<RangeInclusive<usize>>,
+    /// Caption shown above the block, from `title="..."` or `filename="..."`
+    pub filename: Option<String>,
+}
+
+impl CodeFence {
+    /// Parses `rust {3-5,8} title="src/main.rs"`. Unknown attributes are ignored.
+    pub fn parse(info: &str) -> Self {
+        let mut fence = CodeFence::default();
+        let mut rest = info.trim();
+
+        while !rest.is_empty() {
+            let token_end = if let Some(body) = rest.strip_prefix('{') {
+                body.find('}').map(|i| i + 2).unwrap_or(rest.len())
+            } else {
+                attribute_end(rest)
+            };
+            let (token, tail) = rest.split_at(token_end);
+            rest = tail.trim_start();
+
+            if let Some(ranges) = token.strip_prefix('{') {
+                fence
+                    .highlight
+                    .extend(parse_ranges(ranges.trim_end_matches('}')));
+            } else if let Some((key, value)) = token.split_once('=') {
+                if key == "title" || key == "filename" {
+                    fence.filename = Some(value.trim_matches('"').to_string());
+                }
+            } else if fence.lang.is_none() {
+                fence.lang = Some(token.to_string());
+            }
+        }
+
+        fence
+    }
+
+    fn is_highlighted(&self, line: usize) -> bool {
+        self.highlight.iter().any(|range| range.contains(&line))
+    }
+}
+
+/// Length of the next whitespace separated token, keeping quoted values together
+fn attribute_end(text: &str) -> usize {
+    let mut in_quotes = false;
+    for (i, c) in text.char_indices() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            c if c.is_whitespace() && !in_quotes => return i,
+            _ => {}
+        }
+    }
+    text.len()
+}
+
+/// Parses `3-5,8` into `[3..=5, 8..=8]`, skipping anything malformed
+fn parse_ranges(text: &str) -> Vec<RangeInclusive<usize>> {
+    text.split(',')
+        .filter_map(|part| {
+            let part = part.trim();
+            match part.split_once('-') {
+                Some((start, end)) => Some(start.trim().parse().ok()?..=end.trim().parse().ok()?),
+                None => {
+                    let line = part.parse().ok()?;
+                    Some(line..=line)
+                }
+            }
+        })
+        .collect()
+}
+
+/// Renders a code block to HTML with highlighted tokens, line numbers,
+/// marked lines and an optional filename caption.
+pub fn highlight_code(code: &str, fence: &CodeFence) -> String {
+    let syntax = fence
+        .lang
+        .as_deref()
+        .and_then(|lang| SYNTAXES.find_syntax_by_token(lang))
+        .unwrap_or_else(|| SYNTAXES.find_syntax_plain_text());
+
+    let mut generator = ClassedHTMLGenerator::new_with_class_style(syntax, &SYNTAXES, CLASS_STYLE);
+    for line in LinesWithEndings::from(code) {
+        if generator
+            .parse_html_for_line_which_includes_newline(line)
+            .is_err()
+        {
+            // Give up on highlighting rather than dropping the code
+            return render_block(&plain_lines(code), fence);
+        }
+    }
+
+    render_block(&split_lines(&generator.finalize()), fence)
+}
+
+fn plain_lines(code: &str) -> Vec<String> {
+    code.lines().map(xml_escape).collect()
+}
+
+/// Splits highlighted HTML into one string per source line, closing spans
+/// that are still open at the end of a line and reopening them on the next
+/// so every line can be wrapped on its own.
+fn split_lines(html: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut open: Vec<&str> = Vec::new();
+    let mut line = String::new();
+    let mut has_text = false;
+    let mut rest = html;
+
+    while !rest.is_empty() {
+        if rest.starts_with("<span") {
+            let end = rest.find('>').map(|i| i + 1).unwrap_or(rest.len());
+            line.push_str(&rest[..end]);
+            open.push(&rest[..end]);
+            rest = &rest[end..];
+        } else if let Some(tail) = rest.strip_prefix("</span>") {
+            line.push_str("</span>");
+            open.pop();
+            rest = tail;
+        } else if let Some(tail) = rest.strip_prefix('\n') {
+            line.push_str(&"</span>".repeat(open.len()));
+            lines.push(std::mem::take(&mut line));
+            line.extend(open.iter().copied());
+            has_text = false;
+            rest = tail;
+        } else {
+            let end = rest.find(['<', '\n']).unwrap_or(rest.len());
+            line.push_str(&rest[..end]);
+            has_text = true;
+            rest = &rest[end..];
+        }
+    }
+
+    if has_text {
+        line.push_str(&"</span>".repeat(open.len()));
+        lines.push(line);
+    }
+    lines
+}
+
+fn render_block(lines: &[String], fence: &CodeFence) -> String {
+    let lang = fence.lang.as_deref().map(xml_escape);
+    let mut html = String::from(r#"<figure class="code-block">"#);
+
+    if let Some(filename) = &fence.filename {
+        html.push_str(&format!(
+            r#"<figcaption class="code-filename">{}</figcaption>"#,
+            xml_escape(filename)
+        ));
+    }
+
+    match &lang {
+        Some(lang) => html.push_str(&format!(
+            r#"<pre data-lang="{lang}"><code class="language-{lang}">"#
+        )),
+        None => html.push_str("<pre><code>"),
+    }
+
+    for (i, line) in lines.iter().enumerate() {
+        let number = i + 1;
+        let class = if fence.is_highlighted(number) {
+            "code-line highlighted"
+        } else {
+            "code-line"
+        };
+        html.push_str(&format!(
+            r#"<span class="{class}"><span class="line-number" aria-hidden="true">{number}</span>{line}</span>"#
+        ));
+        html.push('\n');
+    }
+
+    html.push_str("</code></pre></figure>");
+    html
+}
+
+/// Replaces every code block in a pulldown-cmark event stream with its
+/// highlighted HTML.
+pub fn highlight_code_blocks<'a>(events: impl Iterator<Item = Event<'a>>) -> Vec<Event<'a>> {
+    let mut out = Vec::new();
+    let mut block: Option<(CodeFence, String)> = None;
+
+    for event in events {
+        match event {
+            Event::Start(Tag::CodeBlock(kind)) => {
+                let fence = match kind {
+                    CodeBlockKind::Fenced(info) => CodeFence::parse(&info),
+                    CodeBlockKind::Indented => CodeFence::default(),
+                };
+                block = Some((fence, String::new()));
+            }
+            Event::Text(text) if block.is_some() => {
+                if let Some((_, code)) = block.as_mut() {
+                    code.push_str(&text);
+                }
+            }
+            Event::End(TagEnd::CodeBlock) => {
+                if let Some((fence, code)) = block.take() {
+                    out.push(Event::Html(CowStr::from(highlight_code(&code, &fence))));
+                }
+            }
+            event => out.push(event),
+        }
+    }
+
+    out
+}
@@ -0,0 +1,196 @@
+//! Rewriting the tags of rendered HTML.
+//!
+//! The HTML is split up by html5ever's tokenizer, the way a browser would, so
+//! raw inline HTML in trusted posts, scripts included, comes out intact. Only
+//! start tags are handed to the caller; text, comments and end tags are
+//! written back as they are, re-escaped where the tokenizer decoded them.
+
+use std::cell::{Cell, RefCell};
+
+use html5ever::tendril::StrTendril;
+use html5ever::tokenizer::states::RawKind;
+use html5ever::tokenizer::{
+    BufferQueue, Tag, TagKind, Token, TokenSink, TokenSinkResult, Tokenizer, TokenizerOpts,
+};
+use html5ever::{Attribute, LocalName, Namespace, QualName};
+
+/// A start tag being rewritten by [`rewrite_tags`]
+pub struct StartTag<'a>(&'a mut Tag);
+
+impl StartTag<'_> {
+    /// Lowercase tag name, e.g. `a`
+    pub fn name(&self) -> &str {
+        &self.0.name
+    }
+
+    /// Value of the attribute `name`, if the tag has it
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.0
+            .attrs
+            .iter()
+            .find(|attribute| &*attribute.name.local == name)
+            .map(|attribute| &*attribute.value)
+    }
+
+    /// Sets the attribute `name` to `value`, adding it if it's missing
+    pub fn set_attribute(&mut self, name: &str, value: &str) {
+        let value = StrTendril::from_slice(value);
+        match self
+            .0
+            .attrs
+            .iter_mut()
+            .find(|attribute| &*attribute.name.local == name)
+        {
+            Some(attribute) => attribute.value = value,
+            None => self.0.attrs.push(Attribute {
+                name: QualName::new(None, Namespace::from(""), LocalName::from(name)),
+                value,
+            }),
+        }
+    }
+}
+
+/// `html` with every start tag passed through `rewrite` first
+pub fn rewrite_tags(html: &str, rewrite: impl FnMut(&mut StartTag)) -> String {
+    let writer = Writer {
+        out: RefCell::new(String::with_capacity(html.len())),
+        raw_text: Cell::new(false),
+        rewrite: RefCell::new(rewrite),
+    };
+    let tokenizer = Tokenizer::new(writer, TokenizerOpts::default());
+    let input = BufferQueue::default();
+    input.push_back(StrTendril::from_slice(html));
+    // The writer never blocks on scripts, so this runs to the end
+    let _ = tokenizer.feed(&input);
+    tokenizer.end();
+    tokenizer.sink.out.into_inner()
+}
+
+/// Token sink serializing the tokens back to HTML
+struct Writer<F> {
+    out: RefCell<String>,
+    /// Inside `<script>`, `<style>` and the like, whose text is kept verbatim
+    raw_text: Cell<bool>,
+    rewrite: RefCell<F>,
+}
+
+impl<F: FnMut(&mut StartTag)> TokenSink for Writer<F> {
+    type Handle = ();
+
+    fn process_token(&self, token: Token, _line_number: u64) -> TokenSinkResult<()> {
+        let mut out = self.out.borrow_mut();
+        match token {
+            Token::TagToken(mut tag) if tag.kind == TagKind::StartTag => {
+                (self.rewrite.borrow_mut())(&mut StartTag(&mut tag));
+                out.push('<');
+                out.push_str(&tag.name);
+                for attribute in &tag.attrs {
+                    out.push(' ');
+                    out.push_str(&attribute.name.local);
+                    out.push_str("=\"");
+                    escape(&mut out, &attribute.value, true);
+                    out.push('"');
+                }
+                if tag.self_closing {
+                    out.push_str(" /");
+                }
+                out.push('>');
+
+                // The tokenizer relies on its sink to switch it into the
+                // states for elements whose content isn't markup
+                let raw = match &*tag.name {
+                    "script" => Some(RawKind::ScriptData),
+                    "style" | "xmp" | "iframe" | "noembed" | "noframes" => Some(RawKind::Rawtext),
+                    "title" | "textarea" => Some(RawKind::Rcdata),
+                    _ => None,
+                };
+                self.raw_text
+                    .set(matches!(raw, Some(RawKind::ScriptData | RawKind::Rawtext)));
+                if let Some(raw) = raw {
+                    return TokenSinkResult::RawData(raw);
+                }
+            }
+            Token::TagToken(tag) => {
+                self.raw_text.set(false);
+                out.push_str("</");
+                out.push_str(&tag.name);
+                out.push('>');
+            }
+            Token::CharacterTokens(text) if self.raw_text.get() => out.push_str(&text),
+            Token::CharacterTokens(text) => escape(&mut out, &text, false),
+            Token::NullCharacterToken => out.push('\0'),
+            Token::CommentToken(text) => {
+                out.push_str("<!--");
+                out.push_str(&text);
+                out.push_str("-->");
+            }
+            Token::DoctypeToken(doctype) => {
+                out.push_str("<!DOCTYPE ");
+                out.push_str(doctype.name.as_deref().unwrap_or("html"));
+                out.push('>');
+            }
+            Token::EOFToken | Token::ParseError(_) => {}
+        }
+        TokenSinkResult::Continue
+    }
+}
+
+/// Escapes `text` like html5ever's serializer does
+fn escape(out: &mut String, text: &str, in_attribute: bool) {
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '\u{a0}' => out.push_str("&nbsp;"),
+            '"' if in_attribute => out.push_str("&quot;"),
+            '<' if !in_attribute => out.push_str("&lt;"),
+            '>' if !in_attribute => out.push_str("&gt;"),
+            c => out.push(c),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unchanged(html: &str) -> String {
+        rewrite_tags(html, |_| {})
+    }
+
+    #[test]
+    fn keeps_rendered_html() {
+        let html = r#"<p class="a">Tom &amp; Jerry &lt;3 <a href="/x?a=1&amp;b=2">x</a><br /></p>"#;
+        assert_eq!(unchanged(html), html);
+    }
+
+    #[test]
+    fn keeps_everything_after_raw_markup() {
+        let html = r#"<script>if (a < b && c > "d) {}</script><p>after</p>"#;
+        assert_eq!(unchanged(html), html);
+
+        assert_eq!(
+            unchanged(r#"<p title='say "hi"'>after</p>"#),
+            r#"<p title="say &quot;hi&quot;">after</p>"#
+        );
+        assert_eq!(
+            unchanged("a < b <!-- note --> c"),
+            "a &lt; b <!-- note --> c"
+        );
+        assert_eq!(unchanged("<p>cut off <a href=\"/x"), "<p>cut off ");
+    }
+
+    #[test]
+    fn rewrites_start_tags() {
+        let html = rewrite_tags("<a href='/x'>x</a><img src=y.png>", |tag| {
+            if tag.name() == "a" {
+                let href = format!("https://example.com{}", tag.attribute("href").unwrap());
+                tag.set_attribute("href", &href);
+                tag.set_attribute("rel", "noopener");
+            }
+        });
+        assert_eq!(
+            html,
+            r#"<a href="https://example.com/x" rel="noopener">x</a><img src="y.png">"#
+        );
+    }
+}
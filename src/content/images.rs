@@ -0,0 +1,513 @@
+//! Responsive variants of the images posts and pages use.
+//!
+//! Covers and inline `<img src="/assets/...">` tags are read from the site
+//! root, which is where `/assets` is served from. Each image is resized to
+//! the configured widths, encoded as AVIF and WebP under `{site_root}/img`,
+//! and shrunk to a tiny blurred placeholder. What was generated is recorded
+//! in a JSON file next to the variants, named after a hash of the original,
+//! so an image is only encoded again when it changes. The hash is kept in
+//! memory while the original's size and modification time stay the same,
+//! so parsing doesn't read every image again.
+//!
+//! Encoding is slow, so parsing content only looks up variants that already
+//! exist. Images without them are queued and keep their original `<img>`
+//! until [`ImagePipeline::generate_pending`] has run, either on the
+//! background thread of [`ImagePipeline::spawn_worker`] or during `export`.
+
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    fs, io,
+    path::{Component, Path, PathBuf},
+    sync::{Arc, Condvar, Mutex},
+    time::SystemTime,
+};
+
+use base64::Engine;
+use image::codecs::avif::AvifEncoder;
+use image::imageops::FilterType;
+use image::{ExtendedColorType, ImageEncoder, RgbaImage};
+use sha2::{Digest, Sha256};
+
+use crate::config::ImageConfig;
+use crate::content::xml_escape;
+use crate::models::blog::slugify;
+use crate::models::image::{ImageSource, ResponsiveImage};
+
+/// URL prefix the site root is served under
+const ASSETS_PREFIX: &str = "/assets/";
+/// Directory below the site root the variants are written to
+const OUTPUT_DIR: &str = "img";
+/// Largest side of the placeholder before the browser scales it up
+const PLACEHOLDER_SIZE: u32 = 16;
+/// AVIF encoder speed from 1 (slowest, smallest) to 10. Pages show the
+/// original until its variants are encoded, so this favors speed.
+const AVIF_SPEED: u8 = 8;
+/// Attributes of an inline `<img>` that the generated `<picture>` replaces
+const REPLACED_ATTRIBUTES: &[&str] = &[
+    "src", "srcset", "sizes", "width", "height", "loading", "decoding",
+];
+
+/// Generates and looks up image variants, see the [module docs](self)
+pub struct ImagePipeline {
+    root: PathBuf,
+    config: ImageConfig,
+    queue: Mutex<Queue>,
+    /// Signalled when an image is added to the queue
+    queued: Condvar,
+    /// Variant names of the originals read so far, by path below the site
+    /// root, with the size and modification time they were computed for
+    names: Mutex<HashMap<String, (Stamp, String)>>,
+}
+
+/// Modification time and size of an original
+type Stamp = (SystemTime, u64);
+
+#[derive(Default)]
+struct Queue {
+    /// Paths below the site root whose variants are missing
+    pending: BTreeSet<String>,
+    /// Paths that couldn't be decoded, not retried until the server restarts
+    failed: HashSet<String>,
+}
+
+impl ImagePipeline {
+    pub fn new<P: AsRef<Path>>(site_root: P, config: &ImageConfig) -> Self {
+        Self {
+            root: site_root.as_ref().to_path_buf(),
+            config: config.clone(),
+            queue: Mutex::default(),
+            queued: Condvar::new(),
+            names: Mutex::default(),
+        }
+    }
+
+    /// The image served at `/assets/{path}` if its variants were generated
+    /// before. Otherwise it's queued for [`generate_pending`](Self::generate_pending)
+    /// and `None` is returned. Never encodes anything itself.
+    pub fn lookup(&self, path: &str) -> Option<ResponsiveImage> {
+        let path = path.trim_start_matches('/');
+        let name = match self.name(path) {
+            Ok(name) => name,
+            Err(e) => {
+                eprintln!("Not processing image {}: {}", path, e);
+                return None;
+            }
+        };
+        if let Some(image) = self.read_manifest(&name) {
+            return Some(image);
+        }
+
+        let mut queue = self.queue.lock().unwrap();
+        if !queue.failed.contains(path) && queue.pending.insert(path.to_string()) {
+            self.queued.notify_one();
+        }
+        None
+    }
+
+    /// Generates the variants of every queued image and returns how many
+    /// images got new ones
+    pub fn generate_pending(&self) -> usize {
+        let mut generated = 0;
+        loop {
+            // Paths stay queued while they are encoded, so lookups in the
+            // meantime don't queue them again
+            let next = self.queue.lock().unwrap().pending.first().cloned();
+            let Some(path) = next else {
+                return generated;
+            };
+            let result = self.try_process(&path);
+            let mut queue = self.queue.lock().unwrap();
+            queue.pending.remove(&path);
+            match result {
+                Ok(_) => generated += 1,
+                Err(e) => {
+                    eprintln!("Not processing image {}: {}", path, e);
+                    queue.failed.insert(path);
+                }
+            }
+        }
+    }
+
+    /// Generates queued variants on a background thread. `on_generated` is
+    /// called whenever some were written, so content can be parsed again to
+    /// pick them up.
+    pub fn spawn_worker(self: &Arc<Self>, on_generated: impl Fn() + Send + 'static) {
+        let pipeline = Arc::clone(self);
+        std::thread::spawn(move || loop {
+            {
+                let mut queue = pipeline.queue.lock().unwrap();
+                while queue.pending.is_empty() {
+                    queue = pipeline.queued.wait(queue).unwrap();
+                }
+            }
+            if pipeline.generate_pending() > 0 {
+                on_generated();
+            }
+        });
+    }
+
+    /// Replaces every `<img>` pointing into `/assets` with a `<picture>` of
+    /// its variants. Other attributes of the tag, like `alt`, are kept.
+    pub fn rewrite_html(&self, html: &str) -> String {
+        let mut out = String::with_capacity(html.len());
+        let mut rest = html;
+
+        while let Some(at) = rest.find("<img") {
+            out.push_str(&rest[..at]);
+            let tag = &rest[at..];
+            let Some((attributes, len)) = parse_img_tag(tag) else {
+                out.push_str("<img");
+                rest = &tag["<img".len()..];
+                continue;
+            };
+
+            let image = attributes
+                .iter()
+                .find(|(name, _)| name == "src")
+                .and_then(|(_, src)| src.strip_prefix(ASSETS_PREFIX))
+                .and_then(|path| self.lookup(path));
+            match image {
+                Some(image) => out.push_str(&picture_html(&image, &attributes)),
+                None => out.push_str(&tag[..len]),
+            }
+            rest = &tag[len..];
+        }
+
+        out.push_str(rest);
+        out
+    }
+
+    /// The name the variants of `path` below the site root are stored
+    /// under. Only reads the original if it changed since it was last read.
+    fn name(&self, path: &str) -> io::Result<String> {
+        let stamp = stamp(&fs::metadata(self.resolve(path)?)?)?;
+        if let Some((cached, name)) = self.names.lock().unwrap().get(path) {
+            if *cached == stamp {
+                return Ok(name.clone());
+            }
+        }
+        self.locate(path).map(|(_, name)| name)
+    }
+
+    /// Reads the original at `path` below the site root, returning it with
+    /// the name its variants are stored under
+    fn locate(&self, path: &str) -> io::Result<(Vec<u8>, String)> {
+        let full = self.resolve(path)?;
+        // Taken before reading, so a change in between is noticed next time
+        let stamp = stamp(&fs::metadata(&full)?)?;
+        let bytes = fs::read(&full)?;
+
+        let stem = Path::new(path)
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .map(slugify)
+            .filter(|stem| !stem.is_empty())
+            .unwrap_or_else(|| "image".to_string());
+        let name = format!("{}-{}", stem, self.fingerprint(&bytes));
+        self.names
+            .lock()
+            .unwrap()
+            .insert(path.to_string(), (stamp, name.clone()));
+        Ok((bytes, name))
+    }
+
+    /// Full path of `path` below the site root, refusing paths that leave it
+    fn resolve(&self, path: &str) -> io::Result<PathBuf> {
+        let relative = Path::new(path);
+        if !relative
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)))
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "path must stay inside the site root",
+            ));
+        }
+        Ok(self.root.join(relative))
+    }
+
+    /// The image recorded in the manifest `name`, if it was generated
+    fn read_manifest(&self, name: &str) -> Option<ResponsiveImage> {
+        let manifest = self.root.join(OUTPUT_DIR).join(format!("{}.json", name));
+        fs::read(manifest)
+            .ok()
+            .and_then(|json| serde_json::from_slice(&json).ok())
+    }
+
+    fn try_process(&self, path: &str) -> io::Result<ResponsiveImage> {
+        let (bytes, name) = self.locate(path)?;
+        if let Some(image) = self.read_manifest(&name) {
+            return Ok(image);
+        }
+        let dir = self.root.join(OUTPUT_DIR);
+        let manifest = dir.join(format!("{}.json", name));
+
+        let original = image::load_from_memory(&bytes).map_err(io::Error::other)?;
+        fs::create_dir_all(&dir)?;
+
+        let mut avif = Vec::new();
+        let mut webp = Vec::new();
+        for width in self.widths(original.width()) {
+            let resized = if width == original.width() {
+                original.to_rgba8()
+            } else {
+                original
+                    .resize(width, u32::MAX, FilterType::CatmullRom)
+                    .to_rgba8()
+            };
+            let file = format!("{}-{}", name, width);
+
+            fs::write(
+                dir.join(format!("{}.avif", file)),
+                encode_avif(&resized, self.config.quality)?,
+            )?;
+            avif.push(format!(
+                "{}{}/{}.avif {}w",
+                ASSETS_PREFIX, OUTPUT_DIR, file, width
+            ));
+
+            fs::write(
+                dir.join(format!("{}.webp", file)),
+                encode_webp(&resized, self.config.quality),
+            )?;
+            webp.push(format!(
+                "{}{}/{}.webp {}w",
+                ASSETS_PREFIX, OUTPUT_DIR, file, width
+            ));
+        }
+
+        let placeholder = original
+            .thumbnail(PLACEHOLDER_SIZE, PLACEHOLDER_SIZE)
+            .blur(1.0)
+            .to_rgba8();
+        let image = ResponsiveImage {
+            src: format!("{}{}", ASSETS_PREFIX, path),
+            width: original.width(),
+            height: original.height(),
+            placeholder: format!(
+                "data:image/webp;base64,{}",
+                base64::engine::general_purpose::STANDARD.encode(encode_webp(&placeholder, 40))
+            ),
+            sources: vec![
+                ImageSource {
+                    mime: "image/avif".to_string(),
+                    srcset: avif.join(", "),
+                },
+                ImageSource {
+                    mime: "image/webp".to_string(),
+                    srcset: webp.join(", "),
+                },
+            ],
+            sizes: self.config.sizes.clone(),
+        };
+
+        fs::write(&manifest, serde_json::to_vec(&image)?)?;
+        Ok(image)
+    }
+
+    /// Widths to generate for an image `original` pixels wide: the
+    /// configured ones it's wider than, plus its own width if it's narrower
+    /// than any of them
+    fn widths(&self, original: u32) -> Vec<u32> {
+        let mut widths: Vec<u32> = self
+            .config
+            .widths
+            .iter()
+            .copied()
+            .filter(|width| *width < original)
+            .collect();
+        if widths.len() < self.config.widths.len() {
+            widths.push(original);
+        }
+        widths.sort_unstable();
+        widths.dedup();
+        widths
+    }
+
+    /// Short hash of the original and the settings its variants depend on
+    fn fingerprint(&self, bytes: &[u8]) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(bytes);
+        hasher.update(format!("{:?}:{}", self.config.widths, self.config.quality));
+        hasher.finalize()[..4]
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+}
+
+fn stamp(metadata: &fs::Metadata) -> io::Result<Stamp> {
+    Ok((metadata.modified()?, metadata.len()))
+}
+
+fn encode_avif(image: &RgbaImage, quality: u8) -> io::Result<Vec<u8>> {
+    let mut out = Vec::new();
+    AvifEncoder::new_with_speed_quality(&mut out, AVIF_SPEED, quality)
+        .write_image(
+            image.as_raw(),
+            image.width(),
+            image.height(),
+            ExtendedColorType::Rgba8,
+        )
+        .map_err(io::Error::other)?;
+    Ok(out)
+}
+
+fn encode_webp(image: &RgbaImage, quality: u8) -> Vec<u8> {
+    webp::Encoder::from_rgba(image.as_raw(), image.width(), image.height())
+        .encode(quality as f32)
+        .to_vec()
+}
+
+/// `<picture>` markup for an inline image, keeping `attributes` of the
+/// original tag that the variants don't replace
+fn picture_html(image: &ResponsiveImage, attributes: &[(String, String)]) -> String {
+    let mut html = String::from("<picture>");
+    for source in &image.sources {
+        html.push_str(&format!(
+            r#"<source type="{}" srcset="{}" sizes="{}">"#,
+            source.mime,
+            xml_escape(&source.srcset),
+            xml_escape(&image.sizes)
+        ));
+    }
+
+    html.push_str(&format!(
+        r#"<img src="{}" width="{}" height="{}" loading="lazy" decoding="async""#,
+        xml_escape(&image.src),
+        image.width,
+        image.height
+    ));
+    let mut styled = false;
+    for (name, value) in attributes {
+        if REPLACED_ATTRIBUTES.contains(&name.as_str()) {
+            continue;
+        }
+        styled |= name == "style";
+        html.push_str(&format!(r#" {}="{}""#, name, value.replace('"', "&quot;")));
+    }
+    if !styled {
+        html.push_str(&format!(r#" style="{}""#, image.placeholder_style()));
+    }
+    html.push_str("></picture>");
+    html
+}
+
+/// Attributes of the `<img>` tag `tag` starts with, with their values as
+/// written, and the length of the tag. `None` if it isn't an `<img>` tag.
+fn parse_img_tag(tag: &str) -> Option<(Vec<(String, String)>, usize)> {
+    let bytes = tag.as_bytes();
+    let mut i = "<img".len();
+    if !matches!(bytes.get(i), Some(b) if b.is_ascii_whitespace() || *b == b'/' || *b == b'>') {
+        return None;
+    }
+
+    let mut attributes = Vec::new();
+    loop {
+        while bytes
+            .get(i)
+            .is_some_and(|b| b.is_ascii_whitespace() || *b == b'/')
+        {
+            i += 1;
+        }
+        if *bytes.get(i)? == b'>' {
+            return Some((attributes, i + 1));
+        }
+
+        let start = i;
+        while bytes
+            .get(i)
+            .is_some_and(|b| !b.is_ascii_whitespace() && !matches!(b, b'=' | b'>' | b'/'))
+        {
+            i += 1;
+        }
+        let name = tag[start..i].to_ascii_lowercase();
+        while bytes.get(i).is_some_and(u8::is_ascii_whitespace) {
+            i += 1;
+        }
+
+        let mut value = String::new();
+        if bytes.get(i) == Some(&b'=') {
+            i += 1;
+            while bytes.get(i).is_some_and(u8::is_ascii_whitespace) {
+                i += 1;
+            }
+            let start = i;
+            match bytes.get(i)? {
+                quote @ (b'"' | b'\'') => {
+                    let end = tag[i + 1..].find(*quote as char)? + i + 1;
+                    value = tag[start + 1..end].to_string();
+                    i = end + 1;
+                }
+                _ => {
+                    while bytes
+                        .get(i)
+                        .is_some_and(|b| !b.is_ascii_whitespace() && *b != b'>')
+                    {
+                        i += 1;
+                    }
+                    value = tag[start..i].to_string();
+                }
+            }
+        }
+        attributes.push((name, value));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_follow_changes_to_the_original() {
+        let root = std::env::temp_dir().join(format!("images-test-{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        fs::write(root.join("Cover Photo.png"), b"original").unwrap();
+        let pipeline = ImagePipeline::new(&root, &ImageConfig::default());
+
+        let name = pipeline.name("Cover Photo.png").unwrap();
+        assert!(name.starts_with("cover-photo-"), "{}", name);
+        assert_eq!(pipeline.name("Cover Photo.png").unwrap(), name);
+
+        fs::write(root.join("Cover Photo.png"), b"replaced with another image").unwrap();
+        assert_ne!(pipeline.name("Cover Photo.png").unwrap(), name);
+
+        assert!(pipeline.name("../Cover Photo.png").is_err());
+        assert!(pipeline.name("missing.png").is_err());
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn parses_img_tags() {
+        let html = r#"<img src="a b.png" alt='Café "ünï"' width=10 loading>rest"#;
+        let (attributes, len) = parse_img_tag(html).unwrap();
+        assert_eq!(&html[len..], "rest");
+        assert_eq!(
+            attributes,
+            [
+                ("src".to_string(), "a b.png".to_string()),
+                ("alt".to_string(), r#"Café "ünï""#.to_string()),
+                ("width".to_string(), "10".to_string()),
+                ("loading".to_string(), String::new()),
+            ]
+        );
+
+        let (attributes, len) = parse_img_tag("<IMG SRC=é.png/>").unwrap();
+        assert_eq!(len, "<IMG SRC=é.png/>".len());
+        assert_eq!(attributes, [("src".to_string(), "é.png/".to_string())]);
+        assert_eq!(parse_img_tag("<img/>"), Some((Vec::new(), 6)));
+    }
+
+    #[test]
+    fn rejects_other_and_unterminated_tags() {
+        for tag in [
+            "<imgs src=x>",
+            "<img",
+            "<img src=\"x.png",
+            "<img alt='é",
+            "<img src=x",
+        ] {
+            assert_eq!(parse_img_tag(tag), None, "{}", tag);
+        }
+    }
+}
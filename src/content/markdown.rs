@@ -0,0 +1,81 @@
+//! Frontmatter and markdown rendering shared by posts and pages.
+
+use gray_matter::engine::YAML;
+use gray_matter::Matter;
+use pulldown_cmark::{html, Options, Parser};
+
+use crate::content::error::ContentError;
+use crate::content::highlight::highlight_code_blocks;
+use crate::content::sanitize::{clean_comment, Sanitizer};
+use crate::content::shortcodes::{fill_placeholders, SHORTCODES};
+use crate::content::summary::{summarize, TextStats};
+use crate::content::toc::add_heading_anchors;
+use crate::models::blog::TocEntry;
+
+/// Splits a document into its YAML frontmatter and the markdown after it
+pub fn split_frontmatter(content: &str) -> Result<(serde_json::Value, String), ContentError> {
+    let parsed = Matter::<YAML>::new()
+        .parse::<serde_json::Value>(content)
+        .map_err(|e| ContentError::InvalidYaml {
+            message: e.to_string(),
+        })?;
+
+    let data = parsed.data.ok_or(ContentError::MissingFrontmatter)?;
+    Ok((data, parsed.content))
+}
+
+/// Markdown rendered to HTML, with what was learned along the way
+pub struct RenderedMarkdown {
+    pub html: String,
+    pub toc: Vec<TocEntry>,
+    pub stats: TextStats,
+}
+
+/// Renders markdown with shortcodes, highlighted code blocks and anchored
+/// headings. With a `sanitizer` the finished HTML, shortcodes included, is
+/// cleaned before it is returned.
+pub fn render_markdown(
+    markdown: &str,
+    sanitizer: Option<&Sanitizer>,
+) -> Result<RenderedMarkdown, ContentError> {
+    // Shortcode bodies are cleaned as part of the document they end up in
+    let (markdown, shortcodes) = SHORTCODES.expand(markdown, |body| {
+        render_markdown(body, None).map(|rendered| rendered.html)
+    })?;
+
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_STRIKETHROUGH);
+    options.insert(Options::ENABLE_TABLES);
+    options.insert(Options::ENABLE_FOOTNOTES);
+    options.insert(Options::ENABLE_TASKLISTS);
+    options.insert(Options::ENABLE_HEADING_ATTRIBUTES);
+
+    let events: Vec<_> = Parser::new_ext(&markdown, options).collect();
+    let stats = summarize(&events);
+
+    let events = highlight_code_blocks(events.into_iter());
+    let (events, toc) = add_heading_anchors(events);
+    let mut html = String::new();
+    html::push_html(&mut html, events.into_iter());
+
+    let html = fill_placeholders(&html, &shortcodes);
+    Ok(RenderedMarkdown {
+        html: match sanitizer {
+            Some(sanitizer) => sanitizer.clean(&html),
+            None => html,
+        },
+        toc,
+        stats,
+    })
+}
+
+/// Renders a reader comment: plain markdown with strikethrough, without
+/// shortcodes, highlighting or heading anchors, cleaned by [`clean_comment`]
+pub fn render_comment(markdown: &str) -> String {
+    let mut html = String::new();
+    html::push_html(
+        &mut html,
+        Parser::new_ext(markdown, Options::ENABLE_STRIKETHROUGH),
+    );
+    clean_comment(&html)
+}
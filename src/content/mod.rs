@@ -0,0 +1,71 @@
+//! Server-side content pipeline.
+//!
+//! Everything in here only exists with the `ssr` feature: the markdown files are
+//! parsed once on the server and the results are shared across requests.
+
+pub mod auth;
+pub mod authors;
+#[cfg(feature = "comments")]
+pub mod comments;
+pub mod error;
+pub mod feed;
+pub mod highlight;
+pub mod html;
+pub mod images;
+pub mod markdown;
+pub mod og;
+pub mod pages;
+pub mod preview;
+pub mod rate_limit;
+pub mod sanitize;
+pub mod shortcodes;
+pub mod sitemap;
+pub mod source;
+pub mod store;
+pub mod summary;
+pub mod toc;
+pub mod token;
+pub mod watch;
+
+pub use authors::AuthorRegistry;
+#[cfg(feature = "comments")]
+pub use comments::CommentStore;
+pub use error::{ContentDiagnostic, ContentError};
+pub use images::ImagePipeline;
+pub use pages::PageStore;
+pub use source::{pages_source_from_config, source_from_config, ContentSource};
+pub use store::ContentStore;
+
+use actix_web::web;
+use leptos::prelude::ServerFnError;
+
+/// Pulls the shared [`ContentStore`] out of the actix `app_data` for the
+/// current request. Only usable from server functions and SSR.
+pub async fn use_content_store() -> Result<web::Data<ContentStore>, ServerFnError> {
+    let store = leptos_actix::extract::<web::Data<ContentStore>>().await?;
+    Ok(store)
+}
+
+/// Pulls the shared [`PageStore`] out of the actix `app_data`, like
+/// [`use_content_store`]
+pub async fn use_page_store() -> Result<web::Data<PageStore>, ServerFnError> {
+    let store = leptos_actix::extract::<web::Data<PageStore>>().await?;
+    Ok(store)
+}
+
+/// Pulls the shared [`CommentStore`] out of the actix `app_data`, like
+/// [`use_content_store`]. Only registered when comments are enabled.
+#[cfg(feature = "comments")]
+pub async fn use_comment_store() -> Result<web::Data<CommentStore>, ServerFnError> {
+    let store = leptos_actix::extract::<web::Data<CommentStore>>().await?;
+    Ok(store)
+}
+
+/// Escapes text for use in XML element content and attribute values
+pub(crate) fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
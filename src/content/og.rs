@@ -0,0 +1,337 @@
+//! Open Graph card images for posts without a cover, served at `/og/{slug}.png`.
+//!
+//! Cards are drawn with tiny-skia and the DejaVu Sans fonts embedded from
+//! `fonts/`, so no browser or system fonts are needed. Rendered cards are
+//! kept in [`OgConfig::cache_dir`](crate::config::OgConfig::cache_dir) under
+//! a hash of everything they show, which makes edits to a post produce a new
+//! card.
+
+use std::{fs, io, path::Path, sync::LazyLock};
+
+use ab_glyph::{point, Font, FontRef, GlyphId, PxScale, ScaleFont};
+use sha2::{Digest, Sha256};
+use tiny_skia::{
+    Color, FillRule, GradientStop, LinearGradient, Paint, Path as SkPath, PathBuilder, Pixmap,
+    Point, Rect, SpreadMode, Transform,
+};
+
+use crate::config::SiteConfig;
+use crate::models::blog::BlogPostMeta;
+
+/// Card size recommended by Facebook, LinkedIn and X
+pub const OG_WIDTH: u32 = 1200;
+pub const OG_HEIGHT: u32 = 630;
+
+static REGULAR: LazyLock<FontRef<'static>> = LazyLock::new(|| {
+    FontRef::try_from_slice(include_bytes!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/fonts/DejaVuSans.ttf"
+    )))
+    .expect("embedded font is valid")
+});
+static BOLD: LazyLock<FontRef<'static>> = LazyLock::new(|| {
+    FontRef::try_from_slice(include_bytes!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/fonts/DejaVuSans-Bold.ttf"
+    )))
+    .expect("embedded font is valid")
+});
+
+/// Space between the content and the edges of the card
+const PADDING: f32 = 80.0;
+/// Title sizes to try, largest first, until the title fits in [`TITLE_LINES`]
+const TITLE_SIZES: &[f32] = &[68.0, 58.0, 48.0];
+const TITLE_LINES: usize = 3;
+/// Used when the configured accent color can't be parsed
+const DEFAULT_ACCENT: [u8; 3] = [0x25, 0x63, 0xeb];
+const TEXT: [u8; 3] = [0x11, 0x18, 0x27];
+const MUTED: [u8; 3] = [0x4b, 0x55, 0x63];
+
+/// PNG card for `meta`, from the cache if it was rendered before. Writing a
+/// new card removes the cached cards of earlier versions of the post.
+pub fn og_card(meta: &BlogPostMeta, config: &SiteConfig) -> io::Result<Vec<u8>> {
+    let name = format!("{}-{}.png", meta.slug, fingerprint(meta, config));
+    let file = Path::new(&config.og.cache_dir).join(&name);
+    if let Ok(png) = fs::read(&file) {
+        return Ok(png);
+    }
+
+    let png = render(meta, config)?;
+    fs::create_dir_all(&config.og.cache_dir)?;
+    fs::write(&file, &png)?;
+    remove_stale_cards(&config.og.cache_dir, &meta.slug, &name);
+    Ok(png)
+}
+
+/// Deletes the cached cards of `slug` other than `current`. Only names of
+/// the form `<slug>-<fingerprint>.png` are touched, so posts whose slug
+/// starts with `<slug>-` keep their cards.
+fn remove_stale_cards(cache_dir: &str, slug: &str, current: &str) {
+    let Ok(entries) = fs::read_dir(cache_dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        let Some(name) = name.to_str() else {
+            continue;
+        };
+        let is_stale = name != current
+            && name
+                .strip_prefix(slug)
+                .and_then(|rest| rest.strip_prefix('-'))
+                .and_then(|rest| rest.strip_suffix(".png"))
+                .is_some_and(|hash| hash.len() == 8 && hash.bytes().all(|b| b.is_ascii_hexdigit()));
+        if is_stale {
+            if let Err(e) = fs::remove_file(entry.path()) {
+                eprintln!("Couldn't remove stale card {}: {}", name, e);
+            }
+        }
+    }
+}
+
+/// Short hash of everything the card shows
+fn fingerprint(meta: &BlogPostMeta, config: &SiteConfig) -> String {
+    let mut hasher = Sha256::new();
+    for part in [&meta.title, &config.name, &config.og.accent, &byline(meta)] {
+        hasher.update(part.as_bytes());
+        hasher.update([0]);
+    }
+    hasher.update(meta.tags.join("\0").as_bytes());
+    hasher.finalize()[..4]
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Authors and publication date, shown at the bottom of the card
+fn byline(meta: &BlogPostMeta) -> String {
+    format!(
+        "{} · {}",
+        meta.author_names().join(", "),
+        meta.date.format("%B %-d, %Y")
+    )
+}
+
+fn render(meta: &BlogPostMeta, config: &SiteConfig) -> io::Result<Vec<u8>> {
+    let accent = parse_hex(&config.og.accent).unwrap_or(DEFAULT_ACCENT);
+    let mut pixmap =
+        Pixmap::new(OG_WIDTH, OG_HEIGHT).ok_or_else(|| io::Error::other("invalid card size"))?;
+    let (width, height) = (OG_WIDTH as f32, OG_HEIGHT as f32);
+
+    // Background fading from white into a tint of the accent color, with a
+    // bar of the accent itself along the left edge
+    let mut paint = Paint {
+        shader: LinearGradient::new(
+            Point::from_xy(0.0, 0.0),
+            Point::from_xy(width, height),
+            vec![
+                GradientStop::new(0.0, Color::WHITE),
+                GradientStop::new(1.0, color(tint(accent, 0.12))),
+            ],
+            SpreadMode::Pad,
+            Transform::identity(),
+        )
+        .ok_or_else(|| io::Error::other("invalid gradient"))?,
+        ..Paint::default()
+    };
+    fill_rect(&mut pixmap, 0.0, 0.0, width, height, &paint);
+    paint.set_color(color(accent));
+    fill_rect(&mut pixmap, 0.0, 0.0, 16.0, height, &paint);
+
+    draw_text(
+        &mut pixmap,
+        &BOLD,
+        34.0,
+        PADDING,
+        120.0,
+        &config.name,
+        accent,
+    );
+
+    // Title, shrunk until it fits and cut off if it still doesn't
+    let max_width = width - 2.0 * PADDING;
+    let (size, mut lines) = TITLE_SIZES
+        .iter()
+        .map(|size| (*size, wrap(&BOLD, *size, &meta.title, max_width)))
+        .find(|(_, lines)| lines.len() <= TITLE_LINES)
+        .unwrap_or_else(|| {
+            let size = TITLE_SIZES[TITLE_SIZES.len() - 1];
+            (size, wrap(&BOLD, size, &meta.title, max_width))
+        });
+    if lines.len() > TITLE_LINES {
+        lines.truncate(TITLE_LINES);
+        let last = &mut lines[TITLE_LINES - 1];
+        while !last.is_empty() && text_width(&BOLD, size, &format!("{}…", last)) > max_width {
+            last.pop();
+        }
+        last.push('…');
+    }
+    for (n, line) in lines.iter().enumerate() {
+        let baseline = 210.0 + size + n as f32 * size * 1.2;
+        draw_text(&mut pixmap, &BOLD, size, PADDING, baseline, line, TEXT);
+    }
+
+    // Tags as pills, as many as fit on one line
+    let mut x = PADDING;
+    for tag in &meta.tags {
+        let text_width = text_width(&REGULAR, 24.0, tag);
+        let pill_width = text_width + 40.0;
+        if x + pill_width > width - PADDING {
+            break;
+        }
+        if let Some(pill) = rounded_rect(x, 468.0, pill_width, 44.0, 22.0) {
+            paint.set_color(color(tint(accent, 0.15)));
+            pixmap.fill_path(
+                &pill,
+                &paint,
+                FillRule::Winding,
+                Transform::identity(),
+                None,
+            );
+        }
+        draw_text(&mut pixmap, &REGULAR, 24.0, x + 20.0, 498.0, tag, accent);
+        x += pill_width + 12.0;
+    }
+
+    draw_text(
+        &mut pixmap,
+        &REGULAR,
+        28.0,
+        PADDING,
+        575.0,
+        &byline(meta),
+        MUTED,
+    );
+
+    pixmap.encode_png().map_err(io::Error::other)
+}
+
+fn fill_rect(pixmap: &mut Pixmap, x: f32, y: f32, width: f32, height: f32, paint: &Paint) {
+    if let Some(rect) = Rect::from_xywh(x, y, width, height) {
+        pixmap.fill_rect(rect, paint, Transform::identity(), None);
+    }
+}
+
+fn rounded_rect(x: f32, y: f32, width: f32, height: f32, radius: f32) -> Option<SkPath> {
+    let (right, bottom) = (x + width, y + height);
+    let mut path = PathBuilder::new();
+    path.move_to(x + radius, y);
+    path.line_to(right - radius, y);
+    path.quad_to(right, y, right, y + radius);
+    path.line_to(right, bottom - radius);
+    path.quad_to(right, bottom, right - radius, bottom);
+    path.line_to(x + radius, bottom);
+    path.quad_to(x, bottom, x, bottom - radius);
+    path.line_to(x, y + radius);
+    path.quad_to(x, y, x + radius, y);
+    path.close();
+    path.finish()
+}
+
+/// Glyphs of `text` laid out on a line starting at `x`, with their positions
+fn layout(font: &FontRef<'static>, size: f32, x: f32, text: &str) -> (Vec<(GlyphId, f32)>, f32) {
+    let scaled = font.as_scaled(PxScale::from(size));
+    let mut glyphs = Vec::new();
+    let mut caret = x;
+    let mut previous = None;
+    for c in text.chars() {
+        let id = scaled.glyph_id(c);
+        if let Some(previous) = previous {
+            caret += scaled.kern(previous, id);
+        }
+        glyphs.push((id, caret));
+        caret += scaled.h_advance(id);
+        previous = Some(id);
+    }
+    (glyphs, caret - x)
+}
+
+fn text_width(font: &FontRef<'static>, size: f32, text: &str) -> f32 {
+    layout(font, size, 0.0, text).1
+}
+
+/// Breaks `text` into lines no wider than `max_width` at spaces. Single
+/// words wider than that get a line of their own.
+fn wrap(font: &FontRef<'static>, size: f32, text: &str, max_width: f32) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        let candidate = if line.is_empty() {
+            word.to_string()
+        } else {
+            format!("{} {}", line, word)
+        };
+        if line.is_empty() || text_width(font, size, &candidate) <= max_width {
+            line = candidate;
+        } else {
+            lines.push(std::mem::replace(&mut line, word.to_string()));
+        }
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+/// Draws `text` with its baseline at `baseline`, blending each glyph's
+/// coverage over what's already there
+fn draw_text(
+    pixmap: &mut Pixmap,
+    font: &FontRef<'static>,
+    size: f32,
+    x: f32,
+    baseline: f32,
+    text: &str,
+    rgb: [u8; 3],
+) {
+    let (width, height) = (pixmap.width() as i32, pixmap.height() as i32);
+    let pixels = pixmap.pixels_mut();
+
+    for (id, caret) in layout(font, size, x, text).0 {
+        let glyph = id.with_scale_and_position(size, point(caret, baseline));
+        let Some(outlined) = font.outline_glyph(glyph) else {
+            continue;
+        };
+        let bounds = outlined.px_bounds();
+        outlined.draw(|gx, gy, coverage| {
+            let (px, py) = (
+                bounds.min.x as i32 + gx as i32,
+                bounds.min.y as i32 + gy as i32,
+            );
+            if px < 0 || py < 0 || px >= width || py >= height {
+                return;
+            }
+            let pixel = &mut pixels[(py * width + px) as usize];
+            let alpha = coverage.clamp(0.0, 1.0);
+            let blend =
+                |src: u8, dst: u8| (src as f32 * alpha + dst as f32 * (1.0 - alpha)).round() as u8;
+            if let Some(blended) = tiny_skia::PremultipliedColorU8::from_rgba(
+                blend(rgb[0], pixel.red()),
+                blend(rgb[1], pixel.green()),
+                blend(rgb[2], pixel.blue()),
+                blend(255, pixel.alpha()),
+            ) {
+                *pixel = blended;
+            }
+        });
+    }
+}
+
+fn color([r, g, b]: [u8; 3]) -> Color {
+    Color::from_rgba8(r, g, b, 255)
+}
+
+/// `rgb` mixed into white, `amount` being the share of `rgb`
+fn tint(rgb: [u8; 3], amount: f32) -> [u8; 3] {
+    rgb.map(|c| (255.0 - (255.0 - c as f32) * amount).round() as u8)
+}
+
+/// Parses `#rrggbb`
+fn parse_hex(hex: &str) -> Option<[u8; 3]> {
+    let hex = hex.strip_prefix('#')?;
+    if hex.len() != 6 {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+    Some([channel(0)?, channel(2)?, channel(4)?])
+}
@@ -0,0 +1,182 @@
+//! Standalone markdown pages from `content/pages`.
+
+use std::{
+    collections::HashMap,
+    path::Path,
+    sync::{Arc, RwLock},
+};
+
+use crate::content::error::{ContentDiagnostic, ContentError};
+use crate::config::SiteConfig;
+use crate::content::images::ImagePipeline;
+use crate::content::markdown::{render_markdown, split_frontmatter};
+use crate::content::sanitize::Sanitizer;
+use crate::content::source::ContentSource;
+use crate::content::watch::EntryStore;
+use crate::models::page::{Page, PageMeta};
+
+/// Parses a page. `id` is the source entry, whose file stem becomes the
+/// page path unless the frontmatter sets one.
+pub fn parse_page_content(
+    id: &str,
+    content: &str,
+    sanitizer: Option<&Sanitizer>,
+) -> Result<Page, ContentError> {
+    let (data, markdown) = split_frontmatter(content)?;
+    let mut meta =
+        serde_json::from_value::<PageMeta>(data).map_err(|e| ContentError::InvalidFrontmatter {
+            message: e.to_string(),
+        })?;
+
+    meta.path = meta.path.trim_matches('/').to_string();
+    if meta.path.is_empty() {
+        meta.path = Path::new(id)
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or(id)
+            .to_string();
+    }
+
+    Ok(Page {
+        meta,
+        content: render_markdown(&markdown, sanitizer)?.html,
+    })
+}
+
+/// In-memory store of parsed pages, indexed by path. Works like
+/// [`ContentStore`](crate::content::ContentStore) for posts, without the
+/// extras pages don't need.
+pub struct PageStore {
+    source: Box<dyn ContentSource>,
+    /// Cleans rendered pages when the source isn't trusted
+    sanitizer: Option<Sanitizer>,
+    /// Turns inline images into responsive variants
+    images: Arc<ImagePipeline>,
+    inner: RwLock<PageStoreInner>,
+}
+
+#[derive(Default)]
+struct PageStoreInner {
+    /// Parsed pages keyed by path
+    pages: HashMap<String, Page>,
+    /// Which path each source entry currently provides
+    entries: HashMap<String, String>,
+    /// The reverse of `entries`: which entry provides each path
+    ids: HashMap<String, String>,
+    /// Entries that were rejected, and why
+    errors: HashMap<String, ContentError>,
+}
+
+impl PageStore {
+    /// Parses every entry of `source`. A missing pages directory just means
+    /// there are no pages.
+    pub fn load(
+        source: Box<dyn ContentSource>,
+        images: Arc<ImagePipeline>,
+        config: &SiteConfig,
+    ) -> Self {
+        let store = Self {
+            sanitizer: Sanitizer::for_source(source.as_ref(), config),
+            source,
+            images,
+            inner: RwLock::new(PageStoreInner::default()),
+        };
+
+        match store.source.list() {
+            Ok(ids) => {
+                for id in ids {
+                    store.reload(&id);
+                }
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => eprintln!("Error listing pages: {}", e),
+        }
+
+        store
+    }
+
+    /// Looks up a page by its path, with or without slashes around it
+    pub fn get(&self, path: &str) -> Option<Page> {
+        let path = path.trim_matches('/');
+        self.inner.read().unwrap().pages.get(path).cloned()
+    }
+
+    /// Metadata of all pages, sorted by path
+    pub fn metas(&self) -> Vec<PageMeta> {
+        let mut metas: Vec<PageMeta> = self
+            .inner
+            .read()
+            .unwrap()
+            .pages
+            .values()
+            .map(|page| page.meta.clone())
+            .collect();
+        metas.sort_by(|a, b| a.path.cmp(&b.path));
+        metas
+    }
+
+    /// Problems with entries that are currently left out, by entry id
+    pub fn errors(&self) -> Vec<ContentDiagnostic> {
+        let inner = self.inner.read().unwrap();
+        let mut errors: Vec<ContentDiagnostic> = inner
+            .errors
+            .iter()
+            .map(|(entry, error)| ContentDiagnostic::new(entry, error.clone()))
+            .collect();
+        errors.sort_by(|a, b| a.entry.cmp(&b.entry));
+        errors
+    }
+}
+
+impl EntryStore for PageStore {
+    fn source(&self) -> &dyn ContentSource {
+        self.source.as_ref()
+    }
+
+    fn reload(&self, id: &str) {
+        let parsed = self
+            .source
+            .read(id)
+            .map_err(ContentError::from)
+            .and_then(|content| parse_page_content(id, &content, self.sanitizer.as_ref()))
+            .map(|mut page| {
+                page.content = self.images.rewrite_html(&page.content);
+                page
+            });
+
+        let mut inner = self.inner.write().unwrap();
+        inner.remove(id);
+
+        let result = parsed.and_then(|page| {
+            let path = page.meta.path.clone();
+            if let Some(existing) = inner.ids.get(&path) {
+                return Err(ContentError::DuplicateSlug {
+                    existing: existing.clone(),
+                    slug: path,
+                });
+            }
+            inner.entries.insert(id.to_string(), path.clone());
+            inner.ids.insert(path.clone(), id.to_string());
+            inner.pages.insert(path, page);
+            Ok(())
+        });
+        if let Err(e) = result {
+            eprintln!("Skipping page {}: {}", id, e);
+            inner.errors.insert(id.to_string(), e);
+        }
+    }
+
+    fn remove(&self, id: &str) {
+        self.inner.write().unwrap().remove(id);
+    }
+}
+
+impl PageStoreInner {
+    fn remove(&mut self, id: &str) {
+        self.errors.remove(id);
+        if let Some(path) = self.entries.remove(id) {
+            self.ids.remove(&path);
+            self.pages.remove(&path);
+        }
+    }
+}
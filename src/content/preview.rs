@@ -0,0 +1,39 @@
+//! Signed preview tokens that let editors open unpublished posts.
+//!
+//! A preview token is a [token](crate::content::token) signed for the post's
+//! slug, keyed with the `PREVIEW_SECRET` environment variable.
+
+use chrono::{DateTime, Utc};
+
+use crate::content::token::{sign_token, verify_token};
+
+/// Secret used to sign preview tokens. Previews are disabled when it isn't set.
+pub fn preview_secret() -> Option<String> {
+    std::env::var("PREVIEW_SECRET").ok().filter(|s| !s.is_empty())
+}
+
+/// Creates a token that opens `slug` until `expires`
+pub fn sign_preview(secret: &str, slug: &str, expires: DateTime<Utc>) -> String {
+    sign_token(secret.as_bytes(), slug, expires)
+}
+
+/// Checks that `token` was signed for `slug` and hasn't expired at `now`
+pub fn verify_preview(secret: &str, slug: &str, token: &str, now: DateTime<Utc>) -> bool {
+    verify_token(secret.as_bytes(), slug, token, now)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone};
+
+    use super::*;
+
+    #[test]
+    fn binds_token_to_slug_and_secret() {
+        let now = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+        let token = sign_preview("secret", "draft", now + Duration::hours(1));
+        assert!(verify_preview("secret", "draft", &token, now));
+        assert!(!verify_preview("secret", "other", &token, now));
+        assert!(!verify_preview("other", "draft", &token, now));
+    }
+}
@@ -0,0 +1,96 @@
+//! Telling clients apart for rate limits, and counting what they do.
+//!
+//! Clients are identified by the address the connection comes from. The
+//! `X-Forwarded-For` header is only believed when that address belongs to
+//! one of the configured trusted proxies, since anyone can send it.
+
+use std::{collections::HashMap, sync::Mutex};
+
+use actix_web::HttpRequest;
+use chrono::{DateTime, Duration, Utc};
+use sha2::{Digest, Sha256};
+
+/// Address of the client that sent `request`.
+///
+/// That is the peer address, unless the peer is one of `trusted_proxies`.
+/// Then `X-Forwarded-For` is read from the right, skipping the trusted
+/// proxies, and the first other address is used: entries further left were
+/// written by the client itself and can't be trusted.
+pub fn client_address(request: &HttpRequest, trusted_proxies: &[String]) -> String {
+    let peer = request
+        .peer_addr()
+        .map(|addr| addr.ip().to_string())
+        .unwrap_or_default();
+    let is_trusted = |address: &str| trusted_proxies.iter().any(|proxy| proxy == address);
+    if !is_trusted(&peer) {
+        return peer;
+    }
+
+    request
+        .headers()
+        .get_all("x-forwarded-for")
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .filter(|address| !address.is_empty())
+        .collect::<Vec<_>>()
+        .into_iter()
+        .rev()
+        .find(|address| !is_trusted(address))
+        .map_or(peer, str::to_string)
+}
+
+/// Short hash of [`client_address`], so rate limits can tell clients apart
+/// without storing their addresses
+pub fn client_id(request: &HttpRequest, trusted_proxies: &[String]) -> String {
+    Sha256::digest(client_address(request, trusted_proxies).as_bytes())[..16]
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Counts events per key over a sliding window, in memory. Counts are lost
+/// when the server restarts.
+pub struct RateLimiter {
+    limit: usize,
+    window: Duration,
+    events: Mutex<HashMap<String, Vec<DateTime<Utc>>>>,
+}
+
+impl RateLimiter {
+    /// Allows `limit` events per key within any `window`
+    pub fn new(limit: usize, window: Duration) -> Self {
+        Self {
+            limit,
+            window,
+            events: Mutex::default(),
+        }
+    }
+
+    /// Whether `key` has used up its events at `now`
+    pub fn is_limited(&self, key: &str, now: DateTime<Utc>) -> bool {
+        let mut events = self.events.lock().unwrap();
+        self.forget_before(&mut events, now - self.window);
+        events
+            .get(key)
+            .is_some_and(|times| times.len() >= self.limit)
+    }
+
+    /// Counts an event of `key` at `now`
+    pub fn record(&self, key: &str, now: DateTime<Utc>) {
+        let mut events = self.events.lock().unwrap();
+        self.forget_before(&mut events, now - self.window);
+        events.entry(key.to_string()).or_default().push(now);
+    }
+
+    fn forget_before(
+        &self,
+        events: &mut HashMap<String, Vec<DateTime<Utc>>>,
+        since: DateTime<Utc>,
+    ) {
+        events.retain(|_, times| {
+            times.retain(|time| *time > since);
+            !times.is_empty()
+        });
+    }
+}
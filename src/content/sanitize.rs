@@ -0,0 +1,219 @@
+//! Cleaning rendered HTML from sources that aren't trusted.
+//!
+//! pulldown-cmark passes raw HTML in markdown through untouched, and link
+//! URLs are used as written. For content from guest authors or a database the
+//! rendered HTML is run through an allow-list: unknown tags and attributes,
+//! scripts, event handlers and `javascript:` URLs are removed, and links to
+//! other sites get `rel="noopener noreferrer"`. The allow-list covers
+//! everything the pipeline itself generates, so highlighted code, heading
+//! anchors and shortcodes survive.
+//!
+//! Reader comments go through the much stricter [`clean_comment`] instead.
+
+use std::collections::HashSet;
+
+use ammonia::Builder;
+
+use crate::config::{SanitizeConfig, SiteConfig, Trust};
+use crate::content::html::rewrite_tags;
+use crate::content::source::ContentSource;
+
+/// Tags allowed on top of ammonia's defaults
+const TAGS: &[&str] = &["section", "video", "iframe", "input"];
+
+/// Attributes allowed on every tag
+const GENERIC_ATTRIBUTES: &[&str] = &[
+    "class",
+    "id",
+    "role",
+    "hidden",
+    "aria-hidden",
+    "aria-label",
+    "aria-current",
+    "data-island",
+    "data-label",
+    "data-lang",
+];
+
+/// Attributes allowed on specific tags on top of ammonia's defaults
+const TAG_ATTRIBUTES: &[(&str, &[&str])] = &[
+    ("img", &["loading"]),
+    ("video", &["src", "poster", "controls", "preload"]),
+    (
+        "iframe",
+        &["src", "title", "loading", "allow", "allowfullscreen"],
+    ),
+    ("input", &["type", "checked", "disabled"]),
+];
+
+/// `rel` of links that leave the site
+const EXTERNAL_REL: &str = "noopener noreferrer";
+
+/// Only embeds the `youtube` shortcode produces are allowed to frame other sites
+const IFRAME_SOURCES: &[&str] = &["https://www.youtube-nocookie.com/embed/"];
+
+/// The only tags comments may use: inline formatting, links, code and lists
+const COMMENT_TAGS: &[&str] = &[
+    "p",
+    "br",
+    "strong",
+    "em",
+    "del",
+    "a",
+    "code",
+    "pre",
+    "blockquote",
+    "ul",
+    "ol",
+    "li",
+];
+
+/// `rel` of links in comments, which the site doesn't vouch for
+const COMMENT_REL: &str = "nofollow ugc noopener noreferrer";
+
+/// Allow-list based HTML sanitizer, see the [module docs](self)
+#[derive(Debug, Clone, Default)]
+pub struct Sanitizer {
+    extra_tags: Vec<String>,
+    extra_attributes: Vec<String>,
+}
+
+impl Sanitizer {
+    pub fn new(config: &SanitizeConfig) -> Self {
+        Self {
+            extra_tags: config.tags.clone(),
+            extra_attributes: config.attributes.clone(),
+        }
+    }
+
+    /// The sanitizer for content read from `source`, or `None` if its HTML
+    /// can be kept. `[content] trust` in the site config overrides the
+    /// source's own trust level.
+    pub fn for_source(source: &dyn ContentSource, config: &SiteConfig) -> Option<Self> {
+        match config.content.trust.unwrap_or_else(|| source.trust()) {
+            Trust::Trusted => None,
+            Trust::Untrusted => Some(Self::new(&config.sanitize)),
+        }
+    }
+
+    /// `html` with everything outside the allow-list removed
+    pub fn clean(&self, html: &str) -> String {
+        let mut builder = Builder::default();
+        builder
+            .add_tags(TAGS)
+            .add_tags(self.extra_tags.iter().map(String::as_str))
+            .add_generic_attributes(GENERIC_ATTRIBUTES)
+            .add_generic_attributes(self.extra_attributes.iter().map(String::as_str))
+            .link_rel(None)
+            .attribute_filter(|tag, attribute, value| match (tag, attribute) {
+                ("iframe", "src") if !IFRAME_SOURCES.iter().any(|src| value.starts_with(src)) => {
+                    None
+                }
+                ("input", "type") if value != "checkbox" => None,
+                _ => Some(value.into()),
+            });
+        for (tag, attributes) in TAG_ATTRIBUTES {
+            builder.add_tag_attributes(tag, attributes.iter().copied());
+        }
+        // Tags whose content would otherwise be kept as text
+        builder.clean_content_tags(HashSet::from(["script", "style"]));
+
+        add_external_rel(&builder.clean(html).to_string())
+    }
+}
+
+/// `html` of a reader comment with everything but [`COMMENT_TAGS`] removed.
+/// Links keep only their `href`; there are no classes, ids or `data-*`
+/// attributes, so comments can't borrow the page's styling, anchors or
+/// islands.
+pub fn clean_comment(html: &str) -> String {
+    Builder::empty()
+        .add_tags(COMMENT_TAGS)
+        .add_tag_attributes("a", ["href"])
+        .add_url_schemes(["http", "https", "mailto"])
+        .link_rel(Some(COMMENT_REL))
+        .clean_content_tags(HashSet::from(["script", "style"]))
+        .clean(html)
+        .to_string()
+}
+
+/// Adds [`EXTERNAL_REL`] to every `<a>` whose `href` is absolute
+fn add_external_rel(html: &str) -> String {
+    rewrite_tags(html, |tag| {
+        if tag.name() == "a" && tag.attribute("href").is_some_and(is_external) {
+            tag.set_attribute("rel", EXTERNAL_REL);
+        }
+    })
+}
+
+fn is_external(href: &str) -> bool {
+    ["http://", "https://", "//"]
+        .iter()
+        .any(|scheme| href.starts_with(scheme))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn clean(html: &str) -> String {
+        Sanitizer::default().clean(html)
+    }
+
+    #[test]
+    fn strips_scripts_and_event_handlers() {
+        let html =
+            clean(r#"<p>Hi<script>alert(1)</script></p><img src="a.png" onerror="alert(1)">"#);
+        assert_eq!(html, r#"<p>Hi</p><img src="a.png">"#);
+    }
+
+    #[test]
+    fn strips_javascript_urls() {
+        assert_eq!(clean(r#"<a href="javascript:alert(1)">x</a>"#), "<a>x</a>");
+    }
+
+    #[test]
+    fn keeps_only_allowed_iframe_hosts() {
+        let allowed = r#"<iframe src="https://www.youtube-nocookie.com/embed/abc"></iframe>"#;
+        assert_eq!(clean(allowed), allowed);
+        assert_eq!(
+            clean(r#"<iframe src="https://evil.example/embed/abc"></iframe>"#),
+            "<iframe></iframe>"
+        );
+        assert_eq!(
+            clean(
+                r#"<iframe src="https://www.youtube-nocookie.com.evil.example/embed/"></iframe>"#
+            ),
+            "<iframe></iframe>"
+        );
+    }
+
+    #[test]
+    fn keeps_islands() {
+        let html = r#"<div class="tabs" data-island="tabs"><p>Tab</p></div>"#;
+        assert_eq!(clean(html), html);
+    }
+
+    #[test]
+    fn adds_rel_to_external_links_only() {
+        assert_eq!(
+            clean(r#"<a href="https://example.com" rel="opener">x</a>"#),
+            r#"<a href="https://example.com" rel="noopener noreferrer">x</a>"#
+        );
+        assert_eq!(
+            clean(r#"<a href="/blog">x</a>"#),
+            r#"<a href="/blog">x</a>"#
+        );
+    }
+
+    #[test]
+    fn comments_lose_attributes_and_unknown_tags() {
+        let html = clean_comment(
+            r#"<p class="hidden" id="x">Hi <img src="a.png"><a href="https://example.com" data-island="tabs">x</a></p>"#,
+        );
+        assert_eq!(
+            html,
+            r#"<p>Hi <a href="https://example.com" rel="nofollow ugc noopener noreferrer">x</a></p>"#
+        );
+    }
+}
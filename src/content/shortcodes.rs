@@ -0,0 +1,452 @@
+//! Shortcodes: richer building blocks inside markdown.
+//!
+//! A shortcode is either self-closing, `{{< youtube id="dQw4w9WgXcQ" >}}`, or
+//! wraps markdown, `{{< callout type="warning" >}}...{{< /callout >}}`.
+//! Before the markdown is rendered every shortcode is swapped for an HTML
+//! comment placeholder; once it's rendered the placeholders are replaced with
+//! the output of the shortcode's renderer. Shortcodes inside code blocks and
+//! code spans are left alone.
+//!
+//! Renderers run on the server. A shortcode that needs interactivity renders
+//! markup with a `data-island` attribute and plain-HTML fallback content, and
+//! the browser mounts the matching component from
+//! [`islands`](crate::components::islands) over it.
+
+use std::collections::HashMap;
+use std::ops::Range;
+use std::sync::LazyLock;
+
+use crate::content::error::ContentError;
+use crate::content::xml_escape;
+
+const OPEN: &str = "{{<";
+const CLOSE: &str = ">}}";
+
+/// A shortcode as written in the markdown
+#[derive(Debug)]
+pub struct ShortcodeCall {
+    pub name: String,
+    attrs: Vec<(String, String)>,
+}
+
+impl ShortcodeCall {
+    /// Value of an attribute, if given
+    pub fn attr(&self, key: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Value of an attribute the shortcode can't do without
+    pub fn require(&self, key: &str) -> Result<&str, String> {
+        self.attr(key)
+            .filter(|v| !v.is_empty())
+            .ok_or_else(|| format!("missing `{}` attribute", key))
+    }
+}
+
+/// Turns a call, and for shortcodes with a body its rendered HTML, into HTML.
+/// Errors are reported as [`ContentError::InvalidShortcode`].
+pub type ShortcodeRenderer = fn(&ShortcodeCall, Option<&str>) -> Result<String, String>;
+
+struct Shortcode {
+    has_body: bool,
+    render: ShortcodeRenderer,
+}
+
+/// The shortcodes posts and pages may use, by name
+#[derive(Default)]
+pub struct ShortcodeRegistry {
+    shortcodes: HashMap<&'static str, Shortcode>,
+}
+
+/// The registry the content pipeline renders with
+pub static SHORTCODES: LazyLock<ShortcodeRegistry> = LazyLock::new(ShortcodeRegistry::builtin);
+
+impl ShortcodeRegistry {
+    /// Adds a shortcode. `has_body` shortcodes must be closed with
+    /// `{{< /name >}}`, all others are self-closing.
+    pub fn register(
+        &mut self,
+        name: &'static str,
+        has_body: bool,
+        render: ShortcodeRenderer,
+    ) -> &mut Self {
+        self.shortcodes.insert(name, Shortcode { has_body, render });
+        self
+    }
+
+    /// Callouts, video embeds, figures and tabs
+    pub fn builtin() -> Self {
+        let mut registry = Self::default();
+        registry
+            .register("callout", true, callout)
+            .register("youtube", false, youtube)
+            .register("video", false, video)
+            .register("figure", false, figure)
+            .register("tabs", true, tabs)
+            .register("tab", true, tab);
+        registry
+    }
+
+    /// Replaces every shortcode in `markdown` with a placeholder. Returns the
+    /// rewritten markdown and the HTML for each placeholder, to be put back
+    /// with [`fill_placeholders`]. Bodies are rendered with `render_body`.
+    pub fn expand(
+        &self,
+        markdown: &str,
+        render_body: impl Fn(&str) -> Result<String, ContentError>,
+    ) -> Result<(String, Vec<String>), ContentError> {
+        let code = code_ranges(markdown);
+        let mut output = String::with_capacity(markdown.len());
+        let mut rendered = Vec::new();
+        let mut pos = 0;
+
+        while let Some(tag) = next_tag(markdown, pos, &code) {
+            let tag = tag?;
+            if tag.closing {
+                return Err(invalid(
+                    &tag.call.name,
+                    "closing tag without an opening tag",
+                ));
+            }
+            let name = tag.call.name.as_str();
+            let shortcode =
+                self.shortcodes
+                    .get(name)
+                    .ok_or_else(|| ContentError::UnknownShortcode {
+                        name: name.to_string(),
+                    })?;
+
+            let (end, html) = if shortcode.has_body {
+                let close = find_close(markdown, &tag, &code)?;
+                let body = render_body(markdown[tag.span.end..close.start].trim_matches('\n'))?;
+                let html =
+                    (shortcode.render)(&tag.call, Some(&body)).map_err(|e| invalid(name, &e))?;
+                (close.end, html)
+            } else {
+                let html = (shortcode.render)(&tag.call, None).map_err(|e| invalid(name, &e))?;
+                (tag.span.end, html)
+            };
+
+            // A shortcode on lines of its own becomes an HTML block, anything
+            // else stays inline in its paragraph
+            let line_start = markdown[..tag.span.start].rfind('\n').map_or(0, |i| i + 1);
+            let line_end = markdown[end..]
+                .find('\n')
+                .map_or(markdown.len(), |i| end + i);
+            let block = markdown[line_start..tag.span.start].trim().is_empty()
+                && markdown[end..line_end].trim().is_empty();
+
+            output.push_str(&markdown[pos..tag.span.start]);
+            if block {
+                output.push('\n');
+            }
+            output.push_str(&placeholder(rendered.len()));
+            if block {
+                output.push('\n');
+            }
+            rendered.push(html);
+            pos = end;
+        }
+        output.push_str(&markdown[pos..]);
+
+        Ok((output, rendered))
+    }
+}
+
+/// Puts the rendered shortcodes back in place of their placeholders
+pub fn fill_placeholders(html: &str, rendered: &[String]) -> String {
+    let mut html = html.to_string();
+    for (i, shortcode) in rendered.iter().enumerate() {
+        html = html.replacen(&placeholder(i), shortcode, 1);
+    }
+    html
+}
+
+fn placeholder(i: usize) -> String {
+    format!("<!--shortcode:{}-->", i)
+}
+
+fn invalid(name: &str, message: &str) -> ContentError {
+    ContentError::InvalidShortcode {
+        name: name.to_string(),
+        reason: message.to_string(),
+    }
+}
+
+struct Tag {
+    call: ShortcodeCall,
+    closing: bool,
+    span: Range<usize>,
+}
+
+/// Byte ranges of fenced code blocks and inline code spans
+fn code_ranges(markdown: &str) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut fence: Option<(String, usize)> = None;
+    let mut offset = 0;
+
+    for line in markdown.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        let marker: String = trimmed
+            .chars()
+            .take_while(|&c| c == '`' || c == '~')
+            .collect();
+        let is_fence = marker.len() >= 3
+            && line.len() - trimmed.len() <= 3
+            && marker.chars().all(|c| c == marker.chars().next().unwrap());
+
+        match &fence {
+            Some((open, start)) if is_fence && marker.starts_with(open.as_str()) => {
+                ranges.push(*start..offset + line.len());
+                fence = None;
+            }
+            Some(_) => {}
+            None if is_fence => fence = Some((marker, offset)),
+            None => {
+                // Code spans: text between matching runs of backticks
+                let mut rest = line;
+                let mut at = offset;
+                while let Some(i) = rest.find('`') {
+                    let run = rest[i..].chars().take_while(|&c| c == '`').count();
+                    let ticks = &rest[i..i + run];
+                    match rest[i + run..].find(ticks) {
+                        Some(j) => {
+                            ranges.push(at + i..at + i + run + j + run);
+                            at += i + run + j + run;
+                            rest = &line[at - offset..];
+                        }
+                        None => break,
+                    }
+                }
+            }
+        }
+        offset += line.len();
+    }
+    if let Some((_, start)) = fence {
+        ranges.push(start..markdown.len());
+    }
+    ranges
+}
+
+/// The next shortcode tag at or after `from` that isn't inside code
+fn next_tag(
+    markdown: &str,
+    mut from: usize,
+    code: &[Range<usize>],
+) -> Option<Result<Tag, ContentError>> {
+    loop {
+        let start = from + markdown[from..].find(OPEN)?;
+        if let Some(range) = code.iter().find(|r| r.contains(&start)) {
+            from = range.end;
+            continue;
+        }
+        let Some(len) = markdown[start..].find(CLOSE) else {
+            return Some(Err(invalid("", "unterminated `{{<`")));
+        };
+        let end = start + len + CLOSE.len();
+        return Some(parse_tag(&markdown[start + OPEN.len()..start + len]).map(
+            |(call, closing)| Tag {
+                call,
+                closing,
+                span: start..end,
+            },
+        ));
+    }
+}
+
+/// The `{{< /name >}}` that closes `open`, skipping nested shortcodes of the same name
+fn find_close(
+    markdown: &str,
+    open: &Tag,
+    code: &[Range<usize>],
+) -> Result<Range<usize>, ContentError> {
+    let name = &open.call.name;
+    let mut depth = 0;
+    let mut pos = open.span.end;
+
+    while let Some(tag) = next_tag(markdown, pos, code) {
+        let tag = tag?;
+        pos = tag.span.end;
+        if &tag.call.name != name {
+            continue;
+        }
+        match (tag.closing, depth) {
+            (true, 0) => return Ok(tag.span),
+            (true, _) => depth -= 1,
+            (false, _) => depth += 1,
+        }
+    }
+    Err(invalid(name, &format!("missing `{{{{< /{} >}}}}`", name)))
+}
+
+/// Parses the inside of a tag: `name key="value" ...` or `/name`
+fn parse_tag(inner: &str) -> Result<(ShortcodeCall, bool), ContentError> {
+    let inner = inner.trim();
+    let (closing, inner) = match inner.strip_prefix('/') {
+        Some(rest) => (true, rest.trim_start()),
+        None => (false, inner),
+    };
+    let name_len = inner.find(char::is_whitespace).unwrap_or(inner.len());
+    let name = &inner[..name_len];
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(invalid(name, "expected a shortcode name"));
+    }
+
+    let mut attrs = Vec::new();
+    let mut rest = inner[name_len..].trim_start();
+    while !rest.is_empty() {
+        let Some(eq) = rest.find('=') else {
+            return Err(invalid(
+                name,
+                &format!("expected key=value, found `{}`", rest),
+            ));
+        };
+        let key = rest[..eq].trim();
+        if key.is_empty() || key.contains(char::is_whitespace) {
+            return Err(invalid(
+                name,
+                &format!("expected key=value, found `{}`", rest),
+            ));
+        }
+        rest = &rest[eq + 1..];
+
+        let (value, after) = match rest.chars().next() {
+            Some(quote @ ('"' | '\'')) => {
+                let Some(end) = rest[1..].find(quote) else {
+                    return Err(invalid(name, &format!("unterminated value for `{}`", key)));
+                };
+                (&rest[1..1 + end], &rest[end + 2..])
+            }
+            _ => {
+                let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+                (&rest[..end], &rest[end..])
+            }
+        };
+        attrs.push((key.to_string(), value.to_string()));
+        rest = after.trim_start();
+    }
+    if closing && !attrs.is_empty() {
+        return Err(invalid(name, "closing tags take no attributes"));
+    }
+
+    Ok((
+        ShortcodeCall {
+            name: name.to_string(),
+            attrs,
+        },
+        closing,
+    ))
+}
+
+/// `{{< callout type="note|tip|warning|danger" title="..." >}}markdown{{< /callout >}}`
+fn callout(call: &ShortcodeCall, body: Option<&str>) -> Result<String, String> {
+    let kind = call.attr("type").unwrap_or("note");
+    let default_title = match kind {
+        "note" => "Note",
+        "tip" => "Tip",
+        "warning" => "Warning",
+        "danger" => "Danger",
+        other => return Err(format!("unknown callout type `{}`", other)),
+    };
+    let title = call.attr("title").unwrap_or(default_title);
+
+    Ok(format!(
+        r#"<aside class="callout callout-{}" role="note"><p class="callout-title">{}</p>{}</aside>"#,
+        kind,
+        xml_escape(title),
+        body.unwrap_or_default()
+    ))
+}
+
+/// `{{< youtube id="..." title="..." start="90" >}}`, embedded without cookies
+fn youtube(call: &ShortcodeCall, _: Option<&str>) -> Result<String, String> {
+    let id = call.require("id")?;
+    if !id
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(format!("`{}` is not a YouTube video id", id));
+    }
+    let start = match call.attr("start") {
+        Some(start) => {
+            let secs: u32 = start
+                .parse()
+                .map_err(|_| format!("`start` must be a number of seconds, found `{}`", start))?;
+            format!("?start={}", secs)
+        }
+        None => String::new(),
+    };
+
+    Ok(format!(
+        r#"<div class="video-embed"><iframe src="https://www.youtube-nocookie.com/embed/{}{}" title="{}" loading="lazy" allow="accelerometer; encrypted-media; picture-in-picture" allowfullscreen></iframe></div>"#,
+        id,
+        start,
+        xml_escape(call.attr("title").unwrap_or("YouTube video"))
+    ))
+}
+
+/// `{{< video src="..." poster="..." caption="..." >}}`
+fn video(call: &ShortcodeCall, _: Option<&str>) -> Result<String, String> {
+    let src = call.require("src")?;
+    let poster = call
+        .attr("poster")
+        .map(|poster| format!(r#" poster="{}""#, xml_escape(poster)))
+        .unwrap_or_default();
+
+    Ok(format!(
+        r#"<figure class="video"><video src="{}"{} controls preload="metadata"></video>{}</figure>"#,
+        xml_escape(src),
+        poster,
+        caption(call)
+    ))
+}
+
+/// `{{< figure src="..." alt="..." caption="..." >}}`
+fn figure(call: &ShortcodeCall, _: Option<&str>) -> Result<String, String> {
+    let src = call.require("src")?;
+
+    Ok(format!(
+        r#"<figure><img src="{}" alt="{}" loading="lazy">{}</figure>"#,
+        xml_escape(src),
+        xml_escape(call.attr("alt").unwrap_or_default()),
+        caption(call)
+    ))
+}
+
+fn caption(call: &ShortcodeCall) -> String {
+    call.attr("caption")
+        .map(|caption| format!("<figcaption>{}</figcaption>", xml_escape(caption)))
+        .unwrap_or_default()
+}
+
+/// `{{< tabs >}}` around `{{< tab label="..." >}}` blocks. Without JavaScript
+/// the tabs are shown one after another; the `tabs` island turns them into a
+/// tab bar.
+fn tabs(_: &ShortcodeCall, body: Option<&str>) -> Result<String, String> {
+    let body = body.unwrap_or_default();
+    if !body.contains(r#"class="tab-panel""#) {
+        return Err("expected at least one `tab` inside".to_string());
+    }
+    Ok(format!(
+        r#"<div class="tabs" data-island="tabs">{}</div>"#,
+        body
+    ))
+}
+
+/// A single panel of `{{< tabs >}}`
+fn tab(call: &ShortcodeCall, body: Option<&str>) -> Result<String, String> {
+    let label = xml_escape(call.require("label")?);
+    Ok(format!(
+        r#"<section class="tab-panel" data-label="{}"><p class="tab-label">{}</p><div class="tab-content">{}</div></section>"#,
+        label,
+        label,
+        body.unwrap_or_default()
+    ))
+}
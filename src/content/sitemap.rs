@@ -0,0 +1,136 @@
+//! `sitemap.xml` and `robots.txt` built from the router's static routes and
+//! the listed posts and pages.
+
+use chrono::{DateTime, Utc};
+
+use crate::config::SiteConfig;
+use crate::content::{xml_escape, ContentStore};
+use crate::models::page::PageMeta;
+use crate::routes::admin::ADMIN_PATH;
+
+/// Most URLs a single sitemap file may contain, per the sitemaps.org protocol
+pub const MAX_URLS_PER_SITEMAP: usize = 50_000;
+
+/// Paths of the Leptos router, as produced by `generate_route_list`, shared
+/// with the sitemap handlers through actix `app_data`
+pub struct RoutePaths(pub Vec<String>);
+
+/// A single `<url>` of the sitemap
+pub struct SitemapUrl {
+    /// Absolute URL
+    pub loc: String,
+    pub lastmod: Option<DateTime<Utc>>,
+}
+
+/// All URLs that belong in the sitemap.
+///
+/// `static_paths` are the router's paths without parameters (in actix format,
+/// as produced by `generate_route_list`) outside the admin area, and every listed post is added with
+/// `lastmod` taken from its `updated` field, the source's modification time or
+/// its publication date, in that order. Standalone `pages` follow the posts.
+pub fn sitemap_urls(
+    site_url: &str,
+    static_paths: &[String],
+    store: &ContentStore,
+    pages: &[PageMeta],
+    now: DateTime<Utc>,
+) -> Vec<SitemapUrl> {
+    let mut urls: Vec<SitemapUrl> = static_paths
+        .iter()
+        .filter(|path| !path.contains('{') && !path.starts_with(ADMIN_PATH))
+        .map(|path| SitemapUrl {
+            loc: format!("{}{}", site_url, path),
+            lastmod: None,
+        })
+        .collect();
+
+    urls.extend(store.listed_posts(now).into_iter().map(|post| {
+        let lastmod = post
+            .meta
+            .updated
+            .or_else(|| store.modified(&post.meta.slug).map(DateTime::<Utc>::from))
+            .unwrap_or_else(|| post.meta.published_at());
+
+        SitemapUrl {
+            loc: format!("{}/blog/{}", site_url, post.meta.slug),
+            lastmod: Some(lastmod),
+        }
+    }));
+
+    urls.extend(pages.iter().map(|page| SitemapUrl {
+        loc: format!("{}{}", site_url, page.href()),
+        lastmod: None,
+    }));
+
+    urls
+}
+
+/// A `<urlset>` document
+pub fn urlset(urls: &[SitemapUrl]) -> String {
+    let mut xml = String::new();
+    xml.push_str(r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    xml.push_str(r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#);
+    for url in urls {
+        xml.push_str("<url>");
+        xml.push_str(&format!("<loc>{}</loc>", xml_escape(&url.loc)));
+        if let Some(lastmod) = url.lastmod {
+            xml.push_str(&format!("<lastmod>{}</lastmod>", lastmod.format("%Y-%m-%d")));
+        }
+        xml.push_str("</url>");
+    }
+    xml.push_str("</urlset>");
+    xml
+}
+
+/// A `<sitemapindex>` pointing at `/sitemap-1.xml` through `/sitemap-{count}.xml`
+pub fn sitemap_index(site_url: &str, count: usize) -> String {
+    let mut xml = String::new();
+    xml.push_str(r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    xml.push_str(r#"<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#);
+    for n in 1..=count {
+        xml.push_str(&format!(
+            "<sitemap><loc>{}</loc></sitemap>",
+            xml_escape(&format!("{}/sitemap-{}.xml", site_url, n))
+        ));
+    }
+    xml.push_str("</sitemapindex>");
+    xml
+}
+
+/// Builds the document served at `/sitemap.xml`: the URL set itself, or an
+/// index of numbered sitemaps once there are more than [`MAX_URLS_PER_SITEMAP`] URLs.
+pub fn sitemap(site_url: &str, urls: &[SitemapUrl]) -> String {
+    if urls.len() <= MAX_URLS_PER_SITEMAP {
+        urlset(urls)
+    } else {
+        sitemap_index(site_url, urls.len().div_ceil(MAX_URLS_PER_SITEMAP))
+    }
+}
+
+/// Builds the numbered sitemap `/sitemap-{n}.xml` (1-based), if it exists
+pub fn sitemap_page(urls: &[SitemapUrl], n: usize) -> Option<String> {
+    if urls.len() <= MAX_URLS_PER_SITEMAP {
+        return None;
+    }
+    urls.chunks(MAX_URLS_PER_SITEMAP)
+        .nth(n.checked_sub(1)?)
+        .map(urlset)
+}
+
+/// `robots.txt` that allows everything except `[robots] disallow` and points
+/// crawlers at the sitemap
+pub fn robots(config: &SiteConfig) -> String {
+    let disallow = config.robots.disallow.iter().map(|p| p.trim());
+
+    let mut txt = String::from("User-agent: *\n");
+    let mut any = false;
+    for path in disallow.filter(|p| !p.is_empty()) {
+        txt.push_str(&format!("Disallow: {}\n", path));
+        any = true;
+    }
+    if !any {
+        txt.push_str("Allow: /\n");
+    }
+    txt.push_str(&format!("\nSitemap: {}\n", config.url("/sitemap.xml")));
+    txt
+}
@@ -0,0 +1,329 @@
+use std::{
+    io,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use crate::config::{SiteConfig, Trust};
+
+/// Where the markdown for blog posts comes from.
+///
+/// Entries are identified by an opaque id (a file name for the filesystem and
+/// embedded sources, a row key for SQLite). The content of an entry is the
+/// full markdown document including its frontmatter.
+pub trait ContentSource: Send + Sync {
+    /// Ids of all markdown entries
+    fn list(&self) -> io::Result<Vec<String>>;
+
+    /// Raw markdown of a single entry
+    fn read(&self, id: &str) -> io::Result<String>;
+
+    /// Last modification time of an entry, if the source tracks it
+    fn modified(&self, id: &str) -> io::Result<Option<SystemTime>>;
+
+    /// Creates or replaces an entry. Read-only sources return
+    /// [`io::ErrorKind::Unsupported`].
+    fn write(&self, _id: &str, _content: &str) -> io::Result<()> {
+        Err(read_only())
+    }
+
+    /// Removes an entry. Read-only sources return [`io::ErrorKind::Unsupported`].
+    fn delete(&self, _id: &str) -> io::Result<()> {
+        Err(read_only())
+    }
+
+    /// Directory backing this source, for sources that can be watched for changes
+    fn watch_dir(&self) -> Option<&Path> {
+        None
+    }
+
+    /// Maps a changed path inside [`ContentSource::watch_dir`] back to an entry id
+    fn id_for_path(&self, _path: &Path) -> Option<String> {
+        None
+    }
+
+    /// Whether raw HTML in this source's entries is kept when rendering.
+    /// Sources are untrusted unless they are files the site owner controls.
+    fn trust(&self) -> Trust {
+        Trust::Untrusted
+    }
+}
+
+fn read_only() -> io::Error {
+    io::Error::new(io::ErrorKind::Unsupported, "this content source is read-only")
+}
+
+/// Reads `*.md` files from a directory on disk
+pub struct FsSource {
+    root: PathBuf,
+}
+
+impl FsSource {
+    pub fn new<P: AsRef<Path>>(root: P) -> Self {
+        Self {
+            root: root.as_ref().to_path_buf(),
+        }
+    }
+
+    fn is_markdown(path: &Path) -> bool {
+        path.extension() == Some("md".as_ref())
+    }
+
+    /// Path of the file for `id`, which has to be a markdown file directly
+    /// inside the root
+    fn entry_path(&self, id: &str) -> io::Result<PathBuf> {
+        let path = Path::new(id);
+        let mut components = path.components();
+        match (components.next(), components.next()) {
+            (Some(std::path::Component::Normal(_)), None) if Self::is_markdown(path) => {
+                Ok(self.root.join(path))
+            }
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("`{}` is not a markdown file name", id),
+            )),
+        }
+    }
+}
+
+impl ContentSource for FsSource {
+    fn list(&self) -> io::Result<Vec<String>> {
+        let mut ids: Vec<String> = std::fs::read_dir(&self.root)?
+            .filter_map(Result::ok)
+            .filter(|entry| {
+                entry.file_type().map(|t| t.is_file()).unwrap_or(false)
+                    && Self::is_markdown(&entry.path())
+            })
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .collect();
+        ids.sort();
+        Ok(ids)
+    }
+
+    fn read(&self, id: &str) -> io::Result<String> {
+        std::fs::read_to_string(self.entry_path(id)?)
+    }
+
+    fn modified(&self, id: &str) -> io::Result<Option<SystemTime>> {
+        Ok(Some(std::fs::metadata(self.entry_path(id)?)?.modified()?))
+    }
+
+    fn write(&self, id: &str, content: &str) -> io::Result<()> {
+        std::fs::write(self.entry_path(id)?, content)
+    }
+
+    fn delete(&self, id: &str) -> io::Result<()> {
+        std::fs::remove_file(self.entry_path(id)?)
+    }
+
+    fn watch_dir(&self) -> Option<&Path> {
+        Some(&self.root)
+    }
+
+    fn id_for_path(&self, path: &Path) -> Option<String> {
+        if !Self::is_markdown(path) {
+            return None;
+        }
+        path.file_name()?.to_str().map(str::to_string)
+    }
+
+    fn trust(&self) -> Trust {
+        Trust::Trusted
+    }
+}
+
+/// Content baked into the binary at compile time from `content/blog` or `content/pages`
+#[cfg(feature = "embed")]
+pub struct EmbeddedSource {
+    dir: &'static include_dir::Dir<'static>,
+}
+
+#[cfg(feature = "embed")]
+static EMBEDDED_BLOG: include_dir::Dir<'static> =
+    include_dir::include_dir!("$CARGO_MANIFEST_DIR/content/blog");
+
+#[cfg(feature = "embed")]
+static EMBEDDED_PAGES: include_dir::Dir<'static> =
+    include_dir::include_dir!("$CARGO_MANIFEST_DIR/content/pages");
+
+#[cfg(feature = "embed")]
+impl EmbeddedSource {
+    pub fn new() -> Self {
+        Self { dir: &EMBEDDED_BLOG }
+    }
+
+    /// The embedded `content/pages` directory
+    pub fn pages() -> Self {
+        Self {
+            dir: &EMBEDDED_PAGES,
+        }
+    }
+
+    fn file(&self, id: &str) -> io::Result<&'static include_dir::File<'static>> {
+        self.dir
+            .get_file(id)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("no embedded entry {}", id)))
+    }
+}
+
+#[cfg(feature = "embed")]
+impl Default for EmbeddedSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "embed")]
+impl ContentSource for EmbeddedSource {
+    fn list(&self) -> io::Result<Vec<String>> {
+        Ok(self
+            .dir
+            .files()
+            .filter(|file| FsSource::is_markdown(file.path()))
+            .filter_map(|file| file.path().to_str().map(str::to_string))
+            .collect())
+    }
+
+    fn read(&self, id: &str) -> io::Result<String> {
+        self.file(id)?
+            .contents_utf8()
+            .map(str::to_string)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, format!("{} is not UTF-8", id)))
+    }
+
+    fn modified(&self, id: &str) -> io::Result<Option<SystemTime>> {
+        Ok(self.file(id)?.metadata().map(|meta| meta.modified()))
+    }
+
+    fn trust(&self) -> Trust {
+        Trust::Trusted
+    }
+}
+
+/// Posts stored as rows of a SQLite `posts` table.
+///
+/// Expects `posts (id TEXT PRIMARY KEY, content TEXT NOT NULL, modified_at INTEGER)`
+/// with `modified_at` in seconds since the Unix epoch. The table is created if it
+/// doesn't exist yet.
+#[cfg(feature = "sqlite")]
+pub struct SqliteSource {
+    conn: std::sync::Mutex<rusqlite::Connection>,
+}
+
+#[cfg(feature = "sqlite")]
+impl SqliteSource {
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let conn = rusqlite::Connection::open(path).map_err(io::Error::other)?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS posts (
+                id TEXT PRIMARY KEY,
+                content TEXT NOT NULL,
+                modified_at INTEGER
+            )",
+            (),
+        )
+        .map_err(io::Error::other)?;
+
+        Ok(Self {
+            conn: std::sync::Mutex::new(conn),
+        })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl ContentSource for SqliteSource {
+    fn list(&self) -> io::Result<Vec<String>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare("SELECT id FROM posts ORDER BY id")
+            .map_err(io::Error::other)?;
+        let ids = stmt
+            .query_map((), |row| row.get(0))
+            .map_err(io::Error::other)?
+            .collect::<Result<Vec<String>, _>>()
+            .map_err(io::Error::other)?;
+        Ok(ids)
+    }
+
+    fn read(&self, id: &str) -> io::Result<String> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row("SELECT content FROM posts WHERE id = ?1", [id], |row| row.get(0))
+            .map_err(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => {
+                    io::Error::new(io::ErrorKind::NotFound, format!("no post with id {}", id))
+                }
+                e => io::Error::other(e),
+            })
+    }
+
+    fn modified(&self, id: &str) -> io::Result<Option<SystemTime>> {
+        let conn = self.conn.lock().unwrap();
+        let secs: Option<i64> = conn
+            .query_row("SELECT modified_at FROM posts WHERE id = ?1", [id], |row| row.get(0))
+            .map_err(io::Error::other)?;
+        Ok(secs.map(|secs| SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(secs.max(0) as u64)))
+    }
+
+    fn write(&self, id: &str, content: &str) -> io::Result<()> {
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or_default();
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO posts (id, content, modified_at) VALUES (?1, ?2, ?3)
+            ON CONFLICT (id) DO UPDATE SET content = excluded.content, modified_at = excluded.modified_at",
+            rusqlite::params![id, content, now],
+        )
+        .map_err(io::Error::other)?;
+        Ok(())
+    }
+
+    fn delete(&self, id: &str) -> io::Result<()> {
+        let conn = self.conn.lock().unwrap();
+        let deleted = conn
+            .execute("DELETE FROM posts WHERE id = ?1", [id])
+            .map_err(io::Error::other)?;
+        if deleted == 0 {
+            return Err(io::Error::new(io::ErrorKind::NotFound, format!("no post with id {}", id)));
+        }
+        Ok(())
+    }
+}
+
+/// Which [`ContentSource`] to use. The kind is read from the environment,
+/// the `fs` source reads the blog directory of the site config:
+///
+/// * `CONTENT_SOURCE` - `fs` (default), `embedded` or `sqlite`
+/// * `CONTENT_DB` - database file for the `sqlite` source, defaults to `content.db`
+pub fn source_from_config(config: &SiteConfig) -> io::Result<Box<dyn ContentSource>> {
+    let kind = std::env::var("CONTENT_SOURCE").unwrap_or_else(|_| "fs".to_string());
+
+    match kind.as_str() {
+        "fs" => Ok(Box::new(FsSource::new(&config.content.blog))),
+        #[cfg(feature = "embed")]
+        "embedded" => Ok(Box::new(EmbeddedSource::new())),
+        #[cfg(feature = "sqlite")]
+        "sqlite" => {
+            let db = std::env::var("CONTENT_DB").unwrap_or_else(|_| "content.db".to_string());
+            Ok(Box::new(SqliteSource::open(db)?))
+        }
+        other => Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!(
+                "unknown content source `{}` (is the matching cargo feature enabled?)",
+                other
+            ),
+        )),
+    }
+}
+
+/// Where standalone pages come from: embedded into the binary with
+/// `CONTENT_SOURCE=embedded`, otherwise the pages directory of the site config
+pub fn pages_source_from_config(config: &SiteConfig) -> io::Result<Box<dyn ContentSource>> {
+    match std::env::var("CONTENT_SOURCE").as_deref() {
+        #[cfg(feature = "embed")]
+        Ok("embedded") => Ok(Box::new(EmbeddedSource::pages())),
+        _ => Ok(Box::new(FsSource::new(&config.content.pages))),
+    }
+}
@@ -0,0 +1,416 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+    time::SystemTime,
+};
+
+use chrono::{DateTime, Utc};
+
+use crate::config::SiteConfig;
+use crate::content::authors::AuthorRegistry;
+use crate::content::error::{ContentDiagnostic, ContentError};
+use crate::content::images::ImagePipeline;
+use crate::content::sanitize::Sanitizer;
+use crate::content::source::ContentSource;
+use crate::content::watch::EntryStore;
+use crate::models::author::AuthorListing;
+use crate::models::blog::{slugify, BlogPost, BlogPostMeta, PostNeighbors, SeriesListing};
+use crate::models::search::SearchHit;
+use crate::search::{SearchIndex, StaticSearchIndex};
+use crate::server_functions::posts::{parse_post_content, sort_posts};
+
+/// How much each tag shared with the current post adds to a related post's score
+const RELATED_TAG_WEIGHT: f32 = 1.0;
+/// How much text similarity (0 to 1) adds to a related post's score
+const RELATED_TEXT_WEIGHT: f32 = 3.0;
+
+/// In-memory store of parsed blog posts, indexed by slug.
+///
+/// The store is built once at startup and shared through actix `app_data`.
+/// When [`watch`](crate::content::watch::watch) is running, entries that change on disk are
+/// re-parsed individually instead of rescanning the whole source.
+pub struct ContentStore {
+    source: Box<dyn ContentSource>,
+    /// Author given to posts whose frontmatter doesn't name one
+    default_author: String,
+    /// Profiles the `author` and `authors` ids of posts resolve to
+    authors: AuthorRegistry,
+    /// Cleans rendered posts when the source isn't trusted
+    sanitizer: Option<Sanitizer>,
+    /// Turns covers and inline images into responsive variants
+    images: Arc<ImagePipeline>,
+    inner: RwLock<StoreInner>,
+}
+
+#[derive(Default)]
+struct StoreInner {
+    /// Parsed posts keyed by slug
+    posts: HashMap<String, BlogPost>,
+    /// Which slug each source entry currently provides
+    entries: HashMap<String, String>,
+    /// The reverse of `entries`: which entry provides each slug
+    ids: HashMap<String, String>,
+    /// Source modification time of each post, keyed by slug
+    modified: HashMap<String, SystemTime>,
+    /// Entries that were rejected, and why
+    errors: HashMap<String, ContentError>,
+    /// Full-text index over every post, including unlisted ones
+    search: SearchIndex,
+}
+
+impl ContentStore {
+    /// Parses every entry of `source` and builds the index.
+    pub fn load(
+        source: Box<dyn ContentSource>,
+        authors: AuthorRegistry,
+        images: Arc<ImagePipeline>,
+        config: &SiteConfig,
+    ) -> Self {
+        let store = Self {
+            sanitizer: Sanitizer::for_source(source.as_ref(), config),
+            source,
+            default_author: config.default_author.clone(),
+            authors,
+            images,
+            inner: RwLock::new(StoreInner::default()),
+        };
+
+        match store.source.list() {
+            Ok(ids) => {
+                for id in ids {
+                    store.reload(&id);
+                }
+            }
+            Err(e) => eprintln!("Error listing blog posts: {}", e),
+        }
+
+        store
+    }
+
+    /// The source the store reads posts from
+    pub fn source(&self) -> &dyn ContentSource {
+        self.source.as_ref()
+    }
+
+    /// Looks up a single post by slug
+    pub fn get(&self, slug: &str) -> Option<BlogPost> {
+        self.inner.read().unwrap().posts.get(slug).cloned()
+    }
+
+    /// All posts, sorted by date
+    pub fn posts(&self) -> Vec<BlogPost> {
+        let mut posts: Vec<BlogPost> = self.inner.read().unwrap().posts.values().cloned().collect();
+        sort_posts(&mut posts);
+        posts
+    }
+
+    /// Metadata of all posts, sorted by date
+    pub fn metas(&self) -> Vec<BlogPostMeta> {
+        self.posts().into_iter().map(|p| p.meta).collect()
+    }
+
+    /// When the entry providing `slug` was last modified in its source, if known
+    pub fn modified(&self, slug: &str) -> Option<SystemTime> {
+        self.inner.read().unwrap().modified.get(slug).copied()
+    }
+
+    /// Posts that show up in listings at `now`, sorted by date
+    pub fn listed_posts(&self, now: DateTime<Utc>) -> Vec<BlogPost> {
+        let mut posts = self.posts();
+        posts.retain(|p| p.meta.is_listed(now));
+        posts
+    }
+
+    /// Metadata of posts that show up in listings at `now`, sorted by date
+    pub fn listed_metas(&self, now: DateTime<Utc>) -> Vec<BlogPostMeta> {
+        self.listed_posts(now).into_iter().map(|p| p.meta).collect()
+    }
+
+    /// An author together with the posts they wrote or co-wrote that are
+    /// listed at `now`, sorted by date. Authors come from the registry, or
+    /// for sites without one, from the listed posts crediting them, so
+    /// drafts and scheduled posts don't give anyone a page.
+    pub fn author_listing(&self, id: &str, now: DateTime<Utc>) -> Option<AuthorListing> {
+        let posts: Vec<BlogPostMeta> = self
+            .listed_metas(now)
+            .into_iter()
+            .filter(|meta| meta.has_author(id))
+            .collect();
+        let author = match self.authors.get(id) {
+            Some(author) => author.clone(),
+            None => posts
+                .iter()
+                .flat_map(|meta| &meta.author_profiles)
+                .find(|author| author.id == id)?
+                .clone(),
+        };
+        Some(AuthorListing { author, posts })
+    }
+
+    /// The listed posts published right before and after the post with
+    /// `slug`, and up to `limit` related listed posts ranked by shared tags
+    /// and similarity of their text
+    pub fn neighbors(&self, slug: &str, limit: usize, now: DateTime<Utc>) -> PostNeighbors {
+        let Some(current) = self.get(slug).map(|post| post.meta) else {
+            return PostNeighbors::default();
+        };
+        let listed = self.listed_metas(now);
+        let key = |meta: &BlogPostMeta| (meta.published_at(), meta.slug.clone());
+
+        let prev = listed
+            .iter()
+            .filter(|meta| key(meta) < key(&current))
+            .max_by_key(|meta| key(meta))
+            .cloned();
+        let next = listed
+            .iter()
+            .filter(|meta| key(meta) > key(&current))
+            .min_by_key(|meta| key(meta))
+            .cloned();
+
+        let tags: Vec<String> = current.tags.iter().map(|tag| slugify(tag)).collect();
+        let similarity: HashMap<String, f32> =
+            self.inner.read().unwrap().search.similar(slug).into_iter().collect();
+
+        let mut related: Vec<(f32, BlogPostMeta)> = listed
+            .into_iter()
+            .filter(|meta| meta.slug != slug)
+            .filter_map(|meta| {
+                let shared = tags.iter().filter(|tag| meta.has_tag(tag)).count() as f32;
+                let text = similarity.get(&meta.slug).copied().unwrap_or(0.0);
+                let score = shared * RELATED_TAG_WEIGHT + text * RELATED_TEXT_WEIGHT;
+                (score > 0.0).then_some((score, meta))
+            })
+            .collect();
+        related.sort_by(|a, b| b.0.total_cmp(&a.0).then_with(|| b.1.date.cmp(&a.1.date)));
+
+        PostNeighbors {
+            prev,
+            next,
+            related: related.into_iter().take(limit).map(|(_, meta)| meta).collect(),
+        }
+    }
+
+    /// The listed parts of the series with `slug` at `now`, in reading order
+    pub fn series(&self, slug: &str, now: DateTime<Utc>) -> Option<SeriesListing> {
+        let posts: Vec<BlogPostMeta> = self
+            .listed_metas(now)
+            .into_iter()
+            .filter(|meta| meta.series_slug().as_deref() == Some(slug))
+            .collect();
+        let name = posts.first()?.series.clone()?;
+        Some(SeriesListing::new(name, slug.to_string(), posts))
+    }
+
+    /// Slugs of every series with a listed part at `now`
+    pub fn series_slugs(&self, now: DateTime<Utc>) -> Vec<String> {
+        let mut slugs: Vec<String> = self
+            .listed_metas(now)
+            .iter()
+            .filter_map(BlogPostMeta::series_slug)
+            .collect();
+        slugs.sort();
+        slugs.dedup();
+        slugs
+    }
+
+    /// Ids of registered authors plus everyone credited on a listed post
+    pub fn author_ids(&self, now: DateTime<Utc>) -> Vec<String> {
+        let mut ids: Vec<String> = self.authors.authors().iter().map(|a| a.id.clone()).collect();
+        for meta in self.listed_metas(now) {
+            for author in meta.author_profiles {
+                if !ids.contains(&author.id) {
+                    ids.push(author.id);
+                }
+            }
+        }
+        ids
+    }
+
+    /// Problems with entries that are currently left out of the store, by entry id
+    pub fn errors(&self) -> Vec<ContentDiagnostic> {
+        let inner = self.inner.read().unwrap();
+        let mut errors: Vec<ContentDiagnostic> = inner
+            .errors
+            .iter()
+            .map(|(entry, error)| ContentDiagnostic::new(entry, error.clone()))
+            .collect();
+        errors.sort_by(|a, b| a.entry.cmp(&b.entry));
+        errors
+    }
+
+    /// Posts listed at `now` that match every word of `query`, best match first
+    pub fn search(&self, query: &str, limit: usize, now: DateTime<Utc>) -> Vec<SearchHit> {
+        let inner = self.inner.read().unwrap();
+        inner.search.hits(query, limit, |slug| {
+            let post = inner.posts.get(slug).filter(|p| p.meta.is_listed(now))?;
+            Some(post.meta.clone())
+        })
+    }
+
+    /// Search index of the posts listed at `now`, for searching in the
+    /// browser of a static export
+    pub fn static_search_index(&self, now: DateTime<Utc>) -> StaticSearchIndex {
+        let inner = self.inner.read().unwrap();
+        StaticSearchIndex::new(inner.posts.values().filter(|p| p.meta.is_listed(now)))
+    }
+
+    /// Ids of all entries in the source with the slug each one provides,
+    /// `None` for entries that were rejected
+    pub fn entries(&self) -> Vec<(String, Option<String>)> {
+        let inner = self.inner.read().unwrap();
+        let mut entries: Vec<(String, Option<String>)> = inner
+            .entries
+            .iter()
+            .map(|(id, slug)| (id.clone(), Some(slug.clone())))
+            .chain(inner.errors.keys().map(|id| (id.clone(), None)))
+            .collect();
+        entries.sort();
+        entries
+    }
+
+    /// Parses a markdown document the way entries of the source are parsed,
+    /// without adding it to the store
+    pub fn parse(&self, content: &str) -> Result<BlogPost, ContentError> {
+        let mut post = self.parse_preview(content)?;
+        post.meta.cover = post
+            .meta
+            .cover_image
+            .as_deref()
+            .and_then(|path| self.images.lookup(path));
+        post.content = self.images.rewrite_html(&post.content);
+        Ok(post)
+    }
+
+    /// Like [`parse`](Self::parse), but leaves images as they are written
+    /// instead of looking up or queueing their variants. For previews of
+    /// content that may never be saved.
+    pub fn parse_preview(&self, content: &str) -> Result<BlogPost, ContentError> {
+        let mut post = parse_post_content(content, self.sanitizer.as_ref())?;
+        if post.meta.author.is_empty() {
+            post.meta.author = self.default_author.clone();
+        }
+        post.meta.author_profiles = std::iter::once(&post.meta.author)
+            .chain(&post.meta.authors)
+            .map(|id| self.authors.resolve(id))
+            .collect::<Result<_, _>>()?;
+        Ok(post)
+    }
+
+    /// Writes an entry back to the source and reloads it
+    pub fn write(&self, id: &str, content: &str) -> std::io::Result<()> {
+        self.source.write(id, content)?;
+        self.reload(id);
+        Ok(())
+    }
+
+    /// Deletes an entry from the source along with its post
+    pub fn delete(&self, id: &str) -> std::io::Result<()> {
+        self.source.delete(id)?;
+        self.remove(id);
+        Ok(())
+    }
+
+    /// Re-reads and re-parses a single entry, replacing whatever it provided before.
+    pub fn reload(&self, id: &str) {
+        let parsed = self
+            .source
+            .read(id)
+            .map_err(ContentError::from)
+            .and_then(|content| self.parse(&content));
+        let modified = self.source.modified(id).ok().flatten();
+
+        let freed = {
+            let mut inner = self.inner.write().unwrap();
+            let old_slug = inner.remove(id);
+
+            let result = parsed.and_then(|post| inner.insert(id, post, modified));
+            if let Err(e) = result {
+                eprintln!("Skipping blog post {}: {}", id, e);
+                inner.errors.insert(id.to_string(), e);
+            }
+
+            old_slug.filter(|slug| !inner.posts.contains_key(slug))
+        };
+
+        if let Some(slug) = freed {
+            self.reclaim(&slug);
+        }
+    }
+
+    /// Drops whatever post the given entry provided
+    pub fn remove(&self, id: &str) {
+        let freed = self.inner.write().unwrap().remove(id);
+
+        if let Some(slug) = freed {
+            self.reclaim(&slug);
+        }
+    }
+
+    /// Gives an entry that was rejected as a duplicate of `slug` another go
+    /// now that the slug is free again.
+    fn reclaim(&self, slug: &str) {
+        let waiting = self.inner.read().unwrap().errors.iter().find_map(|(id, e)| match e {
+            ContentError::DuplicateSlug { slug: s, .. } if s == slug => Some(id.clone()),
+            _ => None,
+        });
+
+        if let Some(id) = waiting {
+            self.reload(&id);
+        }
+    }
+}
+
+impl EntryStore for ContentStore {
+    fn source(&self) -> &dyn ContentSource {
+        self.source.as_ref()
+    }
+
+    fn reload(&self, id: &str) {
+        ContentStore::reload(self, id)
+    }
+
+    fn remove(&self, id: &str) {
+        ContentStore::remove(self, id)
+    }
+}
+
+impl StoreInner {
+    /// Adds a post for `id`, unless another entry already provides its slug.
+    fn insert(
+        &mut self,
+        id: &str,
+        post: BlogPost,
+        modified: Option<SystemTime>,
+    ) -> Result<(), ContentError> {
+        let slug = post.meta.slug.clone();
+
+        if let Some(existing) = self.ids.get(&slug) {
+            return Err(ContentError::DuplicateSlug {
+                existing: existing.clone(),
+                slug,
+            });
+        }
+
+        if let Some(modified) = modified {
+            self.modified.insert(slug.clone(), modified);
+        }
+        self.entries.insert(id.to_string(), slug.clone());
+        self.ids.insert(slug.clone(), id.to_string());
+        self.search.add(&post);
+        self.posts.insert(slug, post);
+        Ok(())
+    }
+
+    /// Forgets the entry, its post and any error recorded for it. Returns the
+    /// slug the entry used to provide.
+    fn remove(&mut self, id: &str) -> Option<String> {
+        self.errors.remove(id);
+        let slug = self.entries.remove(id)?;
+        self.ids.remove(&slug);
+        self.posts.remove(&slug);
+        self.modified.remove(&slug);
+        self.search.remove(&slug);
+        Some(slug)
+    }
+}
@@ -0,0 +1,99 @@
+//! Word count, reading time and plain-text excerpts of posts.
+
+use pulldown_cmark::{Event, Tag, TagEnd};
+
+/// Average reading speed used for the estimate
+const WORDS_PER_MINUTE: usize = 200;
+
+/// Longest excerpt before it gets cut, in characters
+const EXCERPT_MAX_CHARS: usize = 200;
+
+/// Numbers derived from the text of a post
+#[derive(Debug, Default, PartialEq)]
+pub struct TextStats {
+    pub word_count: usize,
+    /// Estimated reading time in minutes, at least 1
+    pub reading_time: u32,
+    /// Plain text of the first paragraph, cut at a sentence boundary
+    pub excerpt: String,
+}
+
+/// Counts the words of a post and extracts its excerpt. Code blocks count
+/// towards neither.
+pub fn summarize(events: &[Event]) -> TextStats {
+    let mut word_count = 0;
+    let mut first_paragraph: Option<String> = None;
+    let mut paragraph: Option<String> = None;
+    let mut in_code_block = false;
+
+    for event in events {
+        match event {
+            Event::Start(Tag::CodeBlock(_)) => in_code_block = true,
+            Event::End(TagEnd::CodeBlock) => in_code_block = false,
+            Event::Start(Tag::Paragraph) if first_paragraph.is_none() => {
+                paragraph = Some(String::new());
+            }
+            Event::End(TagEnd::Paragraph) => {
+                if let Some(text) = paragraph.take().filter(|t| !t.trim().is_empty()) {
+                    first_paragraph = Some(text);
+                }
+            }
+            Event::Text(text) | Event::Code(text) if !in_code_block => {
+                word_count += text.split_whitespace().count();
+                if let Some(paragraph) = paragraph.as_mut() {
+                    paragraph.push_str(text);
+                }
+            }
+            Event::SoftBreak | Event::HardBreak => {
+                if let Some(paragraph) = paragraph.as_mut() {
+                    paragraph.push(' ');
+                }
+            }
+            _ => {}
+        }
+    }
+
+    TextStats {
+        word_count,
+        reading_time: word_count.div_ceil(WORDS_PER_MINUTE).max(1) as u32,
+        excerpt: excerpt(first_paragraph.as_deref().unwrap_or_default()),
+    }
+}
+
+/// Shortens `text` to whole sentences that fit in [`EXCERPT_MAX_CHARS`],
+/// falling back to whole words and an ellipsis when even the first sentence
+/// is too long.
+fn excerpt(text: &str) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.chars().count() <= EXCERPT_MAX_CHARS {
+        return text;
+    }
+
+    // Byte offset just past the last sentence end that still fits
+    let mut cut = None;
+    for (i, (offset, c)) in text.char_indices().enumerate() {
+        if i >= EXCERPT_MAX_CHARS {
+            break;
+        }
+        let next = text[offset + c.len_utf8()..].chars().next();
+        if matches!(c, '.' | '!' | '?') && next.is_none_or(char::is_whitespace) {
+            cut = Some(offset + c.len_utf8());
+        }
+    }
+    if let Some(cut) = cut {
+        return text[..cut].to_string();
+    }
+
+    let mut shortened = String::new();
+    for word in text.split(' ') {
+        if shortened.chars().count() + word.chars().count() + 1 > EXCERPT_MAX_CHARS {
+            break;
+        }
+        if !shortened.is_empty() {
+            shortened.push(' ');
+        }
+        shortened.push_str(word);
+    }
+    shortened.push('…');
+    shortened
+}
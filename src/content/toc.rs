@@ -0,0 +1,89 @@
+//! Heading ids, anchor links and the table of contents.
+
+use pulldown_cmark::{CowStr, Event, HeadingLevel, Tag, TagEnd};
+
+use crate::content::xml_escape;
+use crate::models::blog::{slugify, TocEntry};
+
+/// Deepest heading level that makes it into the table of contents
+const TOC_MAX_LEVEL: HeadingLevel = HeadingLevel::H3;
+
+/// Gives every heading a unique, slugified `id` and appends a `#` anchor
+/// link to it. Returns the rewritten events together with the `h2`/`h3`
+/// headings for the table of contents.
+///
+/// Ids written explicitly in the markdown are used instead of the slugified
+/// title. Either kind gets a `-1`, `-2`, ... suffix when it was already
+/// taken by an earlier heading.
+pub fn add_heading_anchors<'a>(events: Vec<Event<'a>>) -> (Vec<Event<'a>>, Vec<TocEntry>) {
+    let mut out = Vec::with_capacity(events.len());
+    let mut toc = Vec::new();
+    let mut used: Vec<String> = Vec::new();
+    let mut heading: Option<(usize, HeadingLevel, String)> = None;
+
+    for event in events {
+        match event {
+            Event::Start(Tag::Heading { level, .. }) => {
+                heading = Some((out.len(), level, String::new()));
+                out.push(event);
+            }
+            Event::Text(ref text) | Event::Code(ref text) if heading.is_some() => {
+                if let Some((_, _, title)) = heading.as_mut() {
+                    title.push_str(text);
+                }
+                out.push(event);
+            }
+            Event::End(TagEnd::Heading(_)) => {
+                if let Some((start, level, title)) = heading.take() {
+                    let id = match &mut out[start] {
+                        Event::Start(Tag::Heading { id, .. }) => {
+                            let base = match id {
+                                Some(explicit) => explicit.to_string(),
+                                None => slugify(&title),
+                            };
+                            let unique = unique_id(base, &used);
+                            *id = Some(CowStr::from(unique.clone()));
+                            unique
+                        }
+                        _ => unreachable!("heading start was recorded at this index"),
+                    };
+
+                    out.push(Event::InlineHtml(CowStr::from(format!(
+                        r##"<a class="heading-anchor" href="#{}" aria-label="Link to this section">#</a>"##,
+                        xml_escape(&id)
+                    ))));
+
+                    if level > HeadingLevel::H1 && level <= TOC_MAX_LEVEL {
+                        toc.push(TocEntry {
+                            id: id.clone(),
+                            title: title.trim().to_string(),
+                            level: level as u8,
+                        });
+                    }
+                    used.push(id);
+                }
+                out.push(event);
+            }
+            event => out.push(event),
+        }
+    }
+
+    (out, toc)
+}
+
+/// `base`, or `base` with the first free numeric suffix if an earlier
+/// heading already uses it
+fn unique_id(base: String, used: &[String]) -> String {
+    let base = match base {
+        base if base.is_empty() => "section".to_string(),
+        base => base,
+    };
+
+    let mut id = base.clone();
+    let mut n = 1;
+    while used.contains(&id) {
+        id = format!("{}-{}", base, n);
+        n += 1;
+    }
+    id
+}
@@ -0,0 +1,112 @@
+//! Signed, expiring tokens, used for preview links and admin sessions.
+//!
+//! A token is `<expiry>.<signature>`, where the expiry is a Unix timestamp and
+//! the signature is a hex encoded HMAC-SHA256 of `<payload>:<expiry>`. The
+//! payload itself isn't part of the token; the verifier has to know it.
+
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+fn mac(key: &[u8], payload: &str, expires: i64) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(format!("{}:{}", payload, expires).as_bytes());
+    mac
+}
+
+/// Token for `payload` that is valid until `expires`
+pub fn sign_token(key: &[u8], payload: &str, expires: DateTime<Utc>) -> String {
+    let expires = expires.timestamp();
+    let signature = mac(key, payload, expires).finalize().into_bytes();
+    let hex: String = signature.iter().map(|b| format!("{:02x}", b)).collect();
+    format!("{}.{}", expires, hex)
+}
+
+/// Checks that `token` was signed with `key` for `payload` and hasn't expired
+/// at `now`
+pub fn verify_token(key: &[u8], payload: &str, token: &str, now: DateTime<Utc>) -> bool {
+    let Some((expires, hex)) = token.split_once('.') else {
+        return false;
+    };
+    let Ok(expires) = expires.parse::<i64>() else {
+        return false;
+    };
+    if expires < now.timestamp() || hex.len() % 2 != 0 {
+        return false;
+    }
+
+    let signature: Option<Vec<u8>> = (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            hex.get(i..i + 2)
+                .and_then(|b| u8::from_str_radix(b, 16).ok())
+        })
+        .collect();
+
+    match signature {
+        Some(signature) => mac(key, payload, expires).verify_slice(&signature).is_ok(),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone};
+
+    use super::*;
+
+    const KEY: &[u8] = b"key";
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap()
+    }
+
+    fn token() -> String {
+        sign_token(KEY, "payload", now() + Duration::hours(1))
+    }
+
+    #[test]
+    fn accepts_valid_token() {
+        assert!(verify_token(KEY, "payload", &token(), now()));
+        let just_before = now() + Duration::minutes(59);
+        assert!(verify_token(KEY, "payload", &token(), just_before));
+    }
+
+    #[test]
+    fn rejects_tampered_token() {
+        let token = token();
+        let (expires, signature) = token.split_once('.').unwrap();
+        let flipped = if signature.starts_with('0') { "1" } else { "0" };
+        let tampered = format!("{}.{}{}", expires, flipped, &signature[1..]);
+        assert!(!verify_token(KEY, "payload", &tampered, now()));
+
+        let extended = format!("{}.{}", expires.parse::<i64>().unwrap() + 3600, signature);
+        assert!(!verify_token(KEY, "payload", &extended, now()));
+
+        let truncated = &token[..token.len() - 2];
+        assert!(!verify_token(KEY, "payload", truncated, now()));
+    }
+
+    #[test]
+    fn rejects_malformed_token() {
+        for token in ["", ".", "abc", "123.", "123.zz", "123.abc", "x.00"] {
+            assert!(!verify_token(KEY, "payload", token, now()), "{}", token);
+        }
+        // Multi-byte characters must not be split by the hex decoding
+        assert!(!verify_token(KEY, "payload", "9999999999.éé", now()));
+    }
+
+    #[test]
+    fn rejects_other_payload_or_key() {
+        assert!(!verify_token(KEY, "other", &token(), now()));
+        assert!(!verify_token(b"other", "payload", &token(), now()));
+    }
+
+    #[test]
+    fn rejects_expired_token() {
+        let later = now() + Duration::hours(2);
+        assert!(!verify_token(KEY, "payload", &token(), later));
+    }
+}
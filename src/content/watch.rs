@@ -0,0 +1,69 @@
+//! Keeping in-memory stores in sync with a watched content directory.
+
+use std::sync::Arc;
+
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+use crate::content::source::ContentSource;
+
+/// A store that can re-read single entries of its [`ContentSource`]
+pub trait EntryStore: Send + Sync + 'static {
+    fn source(&self) -> &dyn ContentSource;
+
+    /// Re-reads an entry that was created or changed
+    fn reload(&self, id: &str);
+
+    /// Drops an entry that no longer exists
+    fn remove(&self, id: &str);
+
+    /// Re-reads every entry of the source, e.g. once the variants of the
+    /// images they use have been generated
+    fn reload_all(&self) {
+        match self.source().list() {
+            Ok(ids) => {
+                for id in ids {
+                    self.reload(&id);
+                }
+            }
+            Err(e) => eprintln!("Error listing content: {}", e),
+        }
+    }
+}
+
+/// Starts watching the source directory of `store` and keeps it in sync.
+///
+/// Returns `None` for sources that have nothing to watch. The returned
+/// watcher must be kept alive for as long as updates are wanted.
+pub fn watch<S: EntryStore>(store: &Arc<S>) -> notify::Result<Option<RecommendedWatcher>> {
+    let Some(dir) = store.source().watch_dir() else {
+        return Ok(None);
+    };
+
+    let watched = Arc::clone(store);
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<Event>| match res {
+        Ok(event) => handle_event(watched.as_ref(), event),
+        Err(e) => eprintln!("Error watching content directory: {}", e),
+    })?;
+    watcher.watch(dir, RecursiveMode::NonRecursive)?;
+    Ok(Some(watcher))
+}
+
+fn handle_event<S: EntryStore>(store: &S, event: Event) {
+    if matches!(event.kind, EventKind::Access(_)) {
+        return;
+    }
+
+    for path in event.paths {
+        let Some(id) = store.source().id_for_path(&path) else {
+            continue;
+        };
+
+        // Renames and editors that write via a temp file show up as a mix of
+        // create/modify/remove events, so just look at what is on disk now.
+        if path.is_file() {
+            store.reload(&id);
+        } else {
+            store.remove(&id);
+        }
+    }
+}
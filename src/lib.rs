@@ -1,13 +1,22 @@
 pub mod app;
 pub mod components;
+pub mod config;
+#[cfg(feature = "ssr")]
+pub mod content;
 pub mod models;
 pub mod routes;
+pub mod search;
 pub mod server_functions;
 
 #[cfg(feature = "hydrate")]
 #[wasm_bindgen::prelude::wasm_bindgen]
 pub fn hydrate() {
     use app::*;
+    use leptos::prelude::*;
     console_error_panic_hook::set_once();
-    leptos::mount::hydrate_body(App);
+    let config = config::SiteConfig::from_document();
+    leptos::mount::hydrate_body(move || {
+        provide_context(config);
+        view! { <App/> }
+    });
 }
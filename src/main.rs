@@ -1,60 +1,196 @@
 #[cfg(feature = "ssr")]
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
-    use actix_files::Files;
     use actix_web::*;
     use leptos::config::get_configuration;
-    use leptos::prelude::*;
-    use leptos_actix::{generate_route_list, LeptosRoutes};
-    use leptos_bplate::app::*;
-    use leptos_meta::MetaTags;
+    use leptos_bplate::config::SiteConfig;
+    use leptos_bplate::content::watch::{watch, EntryStore};
+    use leptos_bplate::content::{
+        pages_source_from_config, source_from_config, AuthorRegistry, ContentStore, ImagePipeline,
+        PageStore,
+    };
+    use std::sync::Arc;
+
+    // `leptos-bplate preview <slug> [hours]` prints a preview link instead of serving
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let [command, slug, rest @ ..] = args.as_slice() {
+        if command == "preview" {
+            return print_preview_link(slug, rest.first().map(String::as_str));
+        }
+    }
+    // `leptos-bplate export [dir]` writes the whole site out as static files
+    if let [command, rest @ ..] = args.as_slice() {
+        if command == "export" {
+            let out = rest.first().map(String::as_str).unwrap_or("dist");
+            return export_site(std::path::Path::new(out)).await;
+        }
+        // `leptos-bplate hash-password` reads a password from stdin and
+        // prints its hash for `[admin] password_hash`
+        if command == "hash-password" {
+            return print_password_hash();
+        }
+        // `leptos-bplate comments [approve|delete <id>...]` moderates comments
+        #[cfg(feature = "comments")]
+        if command == "comments" {
+            return moderate_comments(rest);
+        }
+    }
 
     let conf = get_configuration(None).unwrap();
     let addr = conf.leptos_options.site_addr;
+    let config = SiteConfig::load()?;
+
+    // Parse all posts once up front and keep them in sync with the disk
+    let source = source_from_config(&config)?;
+    let authors = AuthorRegistry::from_config(&config)?;
+    let images = Arc::new(ImagePipeline::new(
+        conf.leptos_options.site_root.as_ref(),
+        &config.images,
+    ));
+    let store = Arc::new(ContentStore::load(source, authors, images.clone(), &config));
+    let _watcher = match watch(&store) {
+        Ok(watcher) => watcher,
+        Err(e) => {
+            eprintln!("Not watching blog directory for changes: {}", e);
+            None
+        }
+    };
+    let pages = Arc::new(PageStore::load(
+        pages_source_from_config(&config)?,
+        images.clone(),
+        &config,
+    ));
+    let _pages_watcher = match watch(&pages) {
+        Ok(watcher) => watcher,
+        Err(e) => {
+            eprintln!("Not watching pages directory for changes: {}", e);
+            None
+        }
+    };
+    // Missing image variants are encoded in the background, after which the
+    // content is parsed again to use them
+    {
+        let (store, pages) = (store.clone(), pages.clone());
+        images.spawn_worker(move || {
+            store.reload_all();
+            pages.reload_all();
+        });
+    }
+
+    #[cfg(feature = "comments")]
+    let comments = match config.comments.enabled {
+        true => Some(Arc::new(open_comment_store(&config)?)),
+        false => None,
+    };
+
+    println!("listening on http://{}", addr);
 
     HttpServer::new(move || {
-        // Generate the list of routes in your Leptos App
-        let routes = generate_route_list(App);
-        let leptos_options = &conf.leptos_options;
-        let site_root = leptos_options.site_root.clone().to_string();
-
-        println!("listening on http://{}", &addr);
-
-        App::new()
-            // serve JS/WASM/CSS from `pkg`
-            .service(Files::new("/pkg", format!("{site_root}/pkg")))
-            // serve other assets from the `assets` directory
-            .service(Files::new("/assets", &site_root))
-            // serve the favicon from /favicon.ico
-            .service(favicon)
-            .leptos_routes(routes, {
-                let leptos_options = leptos_options.clone();
-                move || {
-                    view! {
-                        <!DOCTYPE html>
-                        <html lang="en">
-                            <head>
-                                <meta charset="utf-8"/>
-                                <meta name="viewport" content="width=device-width, initial-scale=1"/>
-                                <AutoReload options=leptos_options.clone() />
-                                <HydrationScripts options=leptos_options.clone()/>
-                                <MetaTags/>
-                            </head>
-                            <body>
-                                <App/>
-                            </body>
-                        </html>
-                    }
-                }
-            })
-            .app_data(web::Data::new(leptos_options.to_owned()))
+        App::new().configure(|cfg| {
+            site(cfg, &conf.leptos_options, &config, &store, &pages, false);
+            #[cfg(feature = "comments")]
+            if let Some(comments) = &comments {
+                cfg.app_data(web::Data::from(comments.clone()));
+            }
+        })
         //.wrap(middleware::Compress::default())
     })
-    .bind(&addr)?
+    .bind(addr)?
     .run()
     .await
 }
 
+/// Registers every route of the site. `static_export` marks the rendered
+/// pages as part of a static export, see [`export_site`].
+#[cfg(feature = "ssr")]
+fn site(
+    cfg: &mut actix_web::web::ServiceConfig,
+    leptos_options: &leptos::config::LeptosOptions,
+    config: &leptos_bplate::config::SiteConfig,
+    store: &std::sync::Arc<leptos_bplate::content::ContentStore>,
+    pages: &std::sync::Arc<leptos_bplate::content::PageStore>,
+    static_export: bool,
+) {
+    use actix_files::Files;
+    use actix_web::web;
+    use leptos::prelude::*;
+    use leptos_actix::{generate_route_list, LeptosRoutes};
+    use leptos_bplate::app::*;
+    use leptos_bplate::config::{use_site_config, SITE_CONFIG_SCRIPT_ID};
+    use leptos_bplate::content::sitemap::RoutePaths;
+    use leptos_bplate::server_functions::static_export::STATIC_EXPORT_META;
+    use leptos_meta::MetaTags;
+
+    // Generate the list of routes in your Leptos App
+    let routes = generate_route_list(App);
+    let site_root = leptos_options.site_root.clone().to_string();
+
+    // Router paths for the sitemap
+    let route_paths = RoutePaths(
+        routes
+            .iter()
+            .map(|route| route.path().to_string())
+            .collect(),
+    );
+
+    // serve JS/WASM/CSS from `pkg`
+    cfg.service(Files::new("/pkg", format!("{site_root}/pkg")))
+        // serve other assets from the `assets` directory
+        .service(Files::new("/assets", &site_root))
+        // serve the favicon from /favicon.ico
+        .service(favicon)
+        .service(content_diagnostics)
+        .service(rss_feed)
+        .service(atom_feed)
+        .service(json_feed)
+        .service(tag_rss_feed)
+        .service(sitemap_xml)
+        .service(sitemap_page_xml)
+        .service(robots_txt)
+        .service(og_image)
+        .leptos_routes_with_context(
+            routes,
+            {
+                // Page links are added per request so edited pages show up
+                // in the navigation without a restart
+                let config = config.clone();
+                let pages = pages.clone();
+                move || {
+                    let mut config = config.clone();
+                    config.add_page_links(&pages.metas());
+                    provide_context(config)
+                }
+            },
+            {
+            let leptos_options = leptos_options.clone();
+            move || {
+                let config_json = use_site_config().to_script_json();
+                view! {
+                    <!DOCTYPE html>
+                    <html lang="en">
+                        <head>
+                            <meta charset="utf-8"/>
+                            <meta name="viewport" content="width=device-width, initial-scale=1"/>
+                            {static_export.then(|| view! { <meta name=STATIC_EXPORT_META content="true"/> })}
+                            <script type="application/json" id=SITE_CONFIG_SCRIPT_ID inner_html=config_json></script>
+                            <AutoReload options=leptos_options.clone() />
+                            <HydrationScripts options=leptos_options.clone()/>
+                            <MetaTags/>
+                        </head>
+                        <body>
+                            <App/>
+                        </body>
+                    </html>
+                }
+            }
+        })
+        .app_data(web::Data::new(leptos_options.to_owned()))
+        .app_data(web::Data::new(config.clone()))
+        .app_data(web::Data::from(store.clone()))
+        .app_data(web::Data::from(pages.clone()))
+        .app_data(web::Data::new(route_paths));
+}
+
 #[cfg(feature = "ssr")]
 #[actix_web::get("favicon.ico")]
 async fn favicon(
@@ -67,6 +203,613 @@ async fn favicon(
     ))?)
 }
 
+/// Prints a signed link that opens an unpublished post for a limited time
+#[cfg(feature = "ssr")]
+fn print_preview_link(slug: &str, hours: Option<&str>) -> std::io::Result<()> {
+    use leptos_bplate::content::preview::{preview_secret, sign_preview};
+
+    let Some(secret) = preview_secret() else {
+        return Err(std::io::Error::other(
+            "PREVIEW_SECRET must be set to create preview links",
+        ));
+    };
+    let hours = match hours {
+        Some(hours) => hours
+            .parse::<i64>()
+            .map_err(|_| std::io::Error::other(format!("invalid number of hours: {}", hours)))?,
+        None => 24,
+    };
+
+    let expires = chrono::Utc::now() + chrono::Duration::hours(hours);
+    println!(
+        "/blog/{}?preview={}",
+        slug,
+        sign_preview(&secret, slug, expires)
+    );
+    Ok(())
+}
+
+/// Reads a password from the first line of stdin and prints its argon2 hash
+#[cfg(feature = "ssr")]
+fn print_password_hash() -> std::io::Result<()> {
+    use leptos_bplate::content::auth::hash_password;
+
+    let mut password = String::new();
+    std::io::stdin().read_line(&mut password)?;
+    let password = password.trim_end_matches(['\r', '\n']);
+    if password.is_empty() {
+        return Err(std::io::Error::other("the password must not be empty"));
+    }
+    println!("{}", hash_password(password).map_err(std::io::Error::other)?);
+    Ok(())
+}
+
+/// The comment store of the site config
+#[cfg(feature = "comments")]
+fn open_comment_store(
+    config: &leptos_bplate::config::SiteConfig,
+) -> std::io::Result<leptos_bplate::content::CommentStore> {
+    use leptos_bplate::content::CommentStore;
+
+    CommentStore::open(&config.comments.db)
+}
+
+/// Lists the comments waiting for approval, or approves or deletes the
+/// comments with the given ids
+#[cfg(feature = "comments")]
+fn moderate_comments(args: &[String]) -> std::io::Result<()> {
+    use leptos_bplate::config::SiteConfig;
+
+    let store = open_comment_store(&SiteConfig::load()?)?;
+    let Some((action, ids)) = args.split_first() else {
+        let pending = store.pending()?;
+        if pending.is_empty() {
+            println!("No comments waiting for approval");
+        }
+        for comment in pending {
+            println!(
+                "#{} on /blog/{}{} by {} at {}",
+                comment.id,
+                comment.slug,
+                comment
+                    .parent_id
+                    .map(|id| format!(" (reply to #{})", id))
+                    .unwrap_or_default(),
+                comment.author,
+                comment.created_at.format("%Y-%m-%d %H:%M")
+            );
+            for line in comment.body.lines() {
+                println!("    {}", line);
+            }
+            println!();
+        }
+        return Ok(());
+    };
+
+    for id in ids {
+        let id = id
+            .parse::<i64>()
+            .map_err(|_| std::io::Error::other(format!("invalid comment id: {}", id)))?;
+        let found = match action.as_str() {
+            "approve" => store.approve(id)?,
+            "delete" => store.delete(id)?,
+            other => {
+                return Err(std::io::Error::other(format!(
+                    "unknown action `{}`, expected `approve` or `delete`",
+                    other
+                )))
+            }
+        };
+        match found {
+            true => println!("{}d #{}", action, id),
+            false => eprintln!("No comment #{}", id),
+        }
+    }
+    Ok(())
+}
+
+/// Renders every page, feed and exported server function response into `out`
+/// together with the contents of the site root, so the site can be served
+/// by any static file host.
+///
+/// Pages go through the same actix app as a running server: each is
+/// requested in-process and its body written to `<path>/index.html`.
+#[cfg(feature = "ssr")]
+async fn export_site(out: &std::path::Path) -> std::io::Result<()> {
+    use actix_web::http::StatusCode;
+    use actix_service::IntoServiceFactory;
+    use actix_web::dev::{AppConfig, ServiceFactory};
+    use actix_web::App;
+    use leptos::config::get_configuration;
+    use leptos::server_fn::ServerFn;
+    use leptos_actix::generate_route_list;
+    use leptos_bplate::config::SiteConfig;
+    use leptos_bplate::content::watch::EntryStore;
+    use leptos_bplate::content::{
+        pages_source_from_config, source_from_config, AuthorRegistry, ContentStore, ImagePipeline,
+        PageStore,
+    };
+    use leptos_bplate::models::blog::PostListPage;
+    use leptos_bplate::routes::admin::ADMIN_PATH;
+    use leptos_bplate::routes::blog::blog_section::{FetchBlogList, DEFAULT_PER_PAGE};
+    use leptos_bplate::server_functions::authors::FetchAuthorPosts;
+    use leptos_bplate::server_functions::pages::FetchPage;
+    use leptos_bplate::server_functions::posts::{FetchBlogPost, FetchPostNeighbors};
+    use leptos_bplate::server_functions::search::FetchSearchIndex;
+    use leptos_bplate::server_functions::series::FetchSeries;
+    use leptos_bplate::server_functions::static_export::static_response_path;
+    use leptos_bplate::server_functions::tags::{tag_summaries, FetchPostsByTag, FetchTags};
+    use std::sync::Arc;
+
+    let conf = get_configuration(None).unwrap();
+    let leptos_options = conf.leptos_options;
+    let mut config = SiteConfig::load()?;
+    // Posting comments needs a running server
+    config.comments.enabled = false;
+    let authors = AuthorRegistry::from_config(&config)?;
+    let images = Arc::new(ImagePipeline::new(
+        leptos_options.site_root.as_ref(),
+        &config.images,
+    ));
+    let store = Arc::new(ContentStore::load(
+        source_from_config(&config)?,
+        authors,
+        images.clone(),
+        &config,
+    ));
+    let page_store = Arc::new(PageStore::load(
+        pages_source_from_config(&config)?,
+        images.clone(),
+        &config,
+    ));
+    // Exported pages should use every image variant, so encode the missing
+    // ones now and parse the content again
+    if images.generate_pending() > 0 {
+        store.reload_all();
+        page_store.reload_all();
+    }
+    // The app is built the way `HttpServer` builds it for each worker, but
+    // called in-process instead of behind a socket
+    let app = App::new()
+        .configure(|cfg| site(cfg, &leptos_options, &config, &store, &page_store, true))
+        .into_factory()
+        .new_service(AppConfig::default())
+        .await
+        .map_err(|_| std::io::Error::other("failed to build the app"))?;
+
+    let now = chrono::Utc::now();
+    let listed = store.listed_metas(now);
+    let tags = tag_summaries(&listed);
+    let author_ids = store.author_ids(now);
+    let series = store.series_slugs(now);
+    let total_pages = PostListPage::paginate(listed, 1, DEFAULT_PER_PAGE).total_pages;
+    let published: Vec<String> = store
+        .posts()
+        .into_iter()
+        .filter(|post| post.meta.is_published(now))
+        .map(|post| post.meta.slug)
+        .collect();
+
+    // Pages, written as `<path>/index.html`
+    let mut pages: Vec<String> = generate_route_list(leptos_bplate::app::App)
+        .iter()
+        .map(|route| route.path().to_string())
+        .filter(|path| !path.contains('{') && !path.starts_with(ADMIN_PATH))
+        .collect();
+    pages.extend((2..=total_pages).map(|n| format!("/blog/page/{}", n)));
+    pages.extend(published.iter().map(|slug| format!("/blog/{}", slug)));
+    pages.extend(tags.iter().map(|tag| format!("/blog/tag/{}", tag.slug)));
+    pages.extend(author_ids.iter().map(|id| format!("/blog/author/{}", id)));
+    pages.extend(series.iter().map(|slug| format!("/blog/series/{}", slug)));
+    pages.extend(page_store.metas().iter().map(|page| page.href()));
+
+    // Files served as-is
+    let mut files: Vec<String> = [
+        "/feed.xml",
+        "/atom.xml",
+        "/feed.json",
+        "/sitemap.xml",
+        "/robots.txt",
+    ]
+    .map(String::from)
+    .to_vec();
+    files.extend(
+        tags.iter()
+            .map(|tag| format!("/blog/tag/{}/feed.xml", tag.slug)),
+    );
+    files.extend(published.iter().map(|slug| format!("/og/{}.png", slug)));
+
+    // Server function calls the pages make, as `(path, query)`
+    let mut calls: Vec<(&str, String)> = vec![
+        (FetchTags::PATH, qs(&FetchTags {})?),
+        (FetchSearchIndex::PATH, qs(&FetchSearchIndex {})?),
+    ];
+    for n in 1..=total_pages.max(1) {
+        let args = FetchBlogList {
+            page: Some(n),
+            per_page: None,
+        };
+        calls.push((FetchBlogList::PATH, qs(&args)?));
+    }
+    for slug in &published {
+        let args = FetchBlogPost {
+            slug: slug.clone(),
+            preview: None,
+        };
+        calls.push((FetchBlogPost::PATH, qs(&args)?));
+        let args = FetchPostNeighbors { slug: slug.clone() };
+        calls.push((FetchPostNeighbors::PATH, qs(&args)?));
+    }
+    for tag in &tags {
+        let args = FetchPostsByTag {
+            tag: tag.slug.clone(),
+        };
+        calls.push((FetchPostsByTag::PATH, qs(&args)?));
+    }
+    for id in &author_ids {
+        let args = FetchAuthorPosts { id: id.clone() };
+        calls.push((FetchAuthorPosts::PATH, qs(&args)?));
+    }
+    for slug in &series {
+        let args = FetchSeries {
+            series: slug.clone(),
+        };
+        calls.push((FetchSeries::PATH, qs(&args)?));
+    }
+    for page in page_store.metas() {
+        let args = FetchPage { path: page.path };
+        calls.push((FetchPage::PATH, qs(&args)?));
+    }
+
+    std::fs::create_dir_all(out)?;
+
+    for page in &pages {
+        let body = export_get(&app, page, StatusCode::OK).await?;
+        let file = format!("{}/index.html", page.trim_end_matches('/'));
+        write_export(out, &file, &body)?;
+    }
+    for file in &files {
+        let body = export_get(&app, file, StatusCode::OK).await?;
+        write_export(out, file, &body)?;
+    }
+    for n in 1.. {
+        let file = format!("/sitemap-{}.xml", n);
+        match export_get(&app, &file, StatusCode::OK).await {
+            Ok(body) => write_export(out, &file, &body)?,
+            Err(_) => break,
+        }
+    }
+    for (path, query) in &calls {
+        let body = export_get(&app, &format!("{}?{}", path, query), StatusCode::OK).await?;
+        write_export(out, &static_response_path(path, query), &body)?;
+    }
+    let not_found = export_get(&app, "/404", StatusCode::NOT_FOUND).await?;
+    write_export(out, "/404.html", &not_found)?;
+
+    // Everything the server hands out from the site root
+    let site_root = std::path::Path::new(leptos_options.site_root.as_ref());
+    if !site_root.is_dir() {
+        return Err(std::io::Error::other(format!(
+            "site root {} not found, build the site with `cargo leptos build` first",
+            site_root.display()
+        )));
+    }
+    copy_dir(site_root, out, &[])?;
+    copy_dir(site_root, &out.join("assets"), &["pkg"])?;
+
+    println!(
+        "exported {} pages, {} files and {} server function responses to {}",
+        pages.len(),
+        files.len(),
+        calls.len(),
+        out.display()
+    );
+    Ok(())
+}
+
+/// Query string the client sends for a `GetUrl` server function call
+#[cfg(feature = "ssr")]
+fn qs(args: &impl serde::Serialize) -> std::io::Result<String> {
+    serde_qs::to_string(args).map_err(std::io::Error::other)
+}
+
+/// Requests `uri` from the in-process app and returns the body, failing
+/// unless the response has the `expected` status
+#[cfg(feature = "ssr")]
+async fn export_get<S, B>(
+    app: &S,
+    uri: &str,
+    expected: actix_web::http::StatusCode,
+) -> std::io::Result<actix_web::web::Bytes>
+where
+    S: actix_web::dev::Service<
+        actix_http::Request,
+        Response = actix_web::dev::ServiceResponse<B>,
+        Error = actix_web::Error,
+    >,
+    B: actix_web::body::MessageBody,
+{
+    let mut req = actix_http::Request::new();
+    req.head_mut().uri = uri.parse().map_err(std::io::Error::other)?;
+    let res = app
+        .call(req)
+        .await
+        .map_err(|e| std::io::Error::other(format!("{}: {}", uri, e)))?;
+    if res.status() != expected {
+        return Err(std::io::Error::other(format!(
+            "{} responded with {}",
+            uri,
+            res.status()
+        )));
+    }
+    actix_web::body::to_bytes(res.into_body())
+        .await
+        .map_err(|e| std::io::Error::other(format!("{}: {}", uri, e.into())))
+}
+
+/// Writes `body` to the file for the URL path `path` below `out`
+#[cfg(feature = "ssr")]
+fn write_export(out: &std::path::Path, path: &str, body: &[u8]) -> std::io::Result<()> {
+    let file = out.join(path.trim_start_matches('/'));
+    if let Some(dir) = file.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(file, body)
+}
+
+/// Recursively copies `from` into `to`, leaving out top-level entries named in `skip`
+#[cfg(feature = "ssr")]
+fn copy_dir(from: &std::path::Path, to: &std::path::Path, skip: &[&str]) -> std::io::Result<()> {
+    std::fs::create_dir_all(to)?;
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        if skip.iter().any(|name| entry.file_name() == *name) {
+            continue;
+        }
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target, &[])?;
+        } else {
+            std::fs::copy(entry.path(), target)?;
+        }
+    }
+    Ok(())
+}
+
+/// RSS 2.0 feed of all listed posts
+#[cfg(feature = "ssr")]
+#[actix_web::get("feed.xml")]
+async fn rss_feed(
+    config: actix_web::web::Data<leptos_bplate::config::SiteConfig>,
+    store: actix_web::web::Data<leptos_bplate::content::ContentStore>,
+) -> actix_web::HttpResponse {
+    use leptos_bplate::content::feed::{rss, FeedInfo};
+
+    let posts = store.listed_posts(chrono::Utc::now());
+    actix_web::HttpResponse::Ok()
+        .content_type("application/rss+xml; charset=utf-8")
+        .body(rss(&FeedInfo::new(&config, "/feed.xml"), &posts))
+}
+
+/// Atom feed of all listed posts
+#[cfg(feature = "ssr")]
+#[actix_web::get("atom.xml")]
+async fn atom_feed(
+    config: actix_web::web::Data<leptos_bplate::config::SiteConfig>,
+    store: actix_web::web::Data<leptos_bplate::content::ContentStore>,
+) -> actix_web::HttpResponse {
+    use leptos_bplate::content::feed::{atom, FeedInfo};
+
+    let posts = store.listed_posts(chrono::Utc::now());
+    actix_web::HttpResponse::Ok()
+        .content_type("application/atom+xml; charset=utf-8")
+        .body(atom(&FeedInfo::new(&config, "/atom.xml"), &posts))
+}
+
+/// JSON Feed of all listed posts
+#[cfg(feature = "ssr")]
+#[actix_web::get("feed.json")]
+async fn json_feed(
+    config: actix_web::web::Data<leptos_bplate::config::SiteConfig>,
+    store: actix_web::web::Data<leptos_bplate::content::ContentStore>,
+) -> actix_web::HttpResponse {
+    use leptos_bplate::content::feed::{json_feed, FeedInfo};
+
+    let posts = store.listed_posts(chrono::Utc::now());
+    actix_web::HttpResponse::Ok()
+        .content_type("application/feed+json; charset=utf-8")
+        .body(json_feed(&FeedInfo::new(&config, "/feed.json"), &posts).to_string())
+}
+
+/// RSS 2.0 feed of the listed posts with a given tag
+#[cfg(feature = "ssr")]
+#[actix_web::get("blog/tag/{tag}/feed.xml")]
+async fn tag_rss_feed(
+    config: actix_web::web::Data<leptos_bplate::config::SiteConfig>,
+    store: actix_web::web::Data<leptos_bplate::content::ContentStore>,
+    tag: actix_web::web::Path<String>,
+) -> actix_web::HttpResponse {
+    use leptos_bplate::content::feed::{rss, FeedInfo};
+    use leptos_bplate::models::blog::slugify;
+
+    let tag = slugify(&tag.into_inner());
+    let mut posts = store.listed_posts(chrono::Utc::now());
+    posts.retain(|p| p.meta.has_tag(&tag));
+    let Some(name) = posts
+        .first()
+        .and_then(|p| p.meta.tags.iter().find(|t| slugify(t) == tag))
+        .cloned()
+    else {
+        return actix_web::HttpResponse::NotFound().finish();
+    };
+
+    let info = FeedInfo::new(&config, &format!("/blog/tag/{}/feed.xml", tag)).for_tag(&name);
+    actix_web::HttpResponse::Ok()
+        .content_type("application/rss+xml; charset=utf-8")
+        .body(rss(&info, &posts))
+}
+
+/// Sitemap of the static routes and listed posts, or an index once it gets too big
+#[cfg(feature = "ssr")]
+#[actix_web::get("sitemap.xml")]
+async fn sitemap_xml(
+    config: actix_web::web::Data<leptos_bplate::config::SiteConfig>,
+    store: actix_web::web::Data<leptos_bplate::content::ContentStore>,
+    pages: actix_web::web::Data<leptos_bplate::content::PageStore>,
+    routes: actix_web::web::Data<leptos_bplate::content::sitemap::RoutePaths>,
+) -> actix_web::HttpResponse {
+    use leptos_bplate::content::sitemap;
+
+    let urls = sitemap::sitemap_urls(
+        &config.base_url,
+        &routes.0,
+        &store,
+        &pages.metas(),
+        chrono::Utc::now(),
+    );
+    actix_web::HttpResponse::Ok()
+        .content_type("application/xml; charset=utf-8")
+        .body(sitemap::sitemap(&config.base_url, &urls))
+}
+
+/// One of the numbered sitemaps listed in the sitemap index
+#[cfg(feature = "ssr")]
+#[actix_web::get("sitemap-{n}.xml")]
+async fn sitemap_page_xml(
+    config: actix_web::web::Data<leptos_bplate::config::SiteConfig>,
+    store: actix_web::web::Data<leptos_bplate::content::ContentStore>,
+    pages: actix_web::web::Data<leptos_bplate::content::PageStore>,
+    routes: actix_web::web::Data<leptos_bplate::content::sitemap::RoutePaths>,
+    n: actix_web::web::Path<usize>,
+) -> actix_web::HttpResponse {
+    use leptos_bplate::content::sitemap;
+
+    let urls = sitemap::sitemap_urls(
+        &config.base_url,
+        &routes.0,
+        &store,
+        &pages.metas(),
+        chrono::Utc::now(),
+    );
+    match sitemap::sitemap_page(&urls, n.into_inner()) {
+        Some(xml) => actix_web::HttpResponse::Ok()
+            .content_type("application/xml; charset=utf-8")
+            .body(xml),
+        None => actix_web::HttpResponse::NotFound().finish(),
+    }
+}
+
+#[cfg(feature = "ssr")]
+#[actix_web::get("robots.txt")]
+async fn robots_txt(
+    config: actix_web::web::Data<leptos_bplate::config::SiteConfig>,
+) -> actix_web::HttpResponse {
+    actix_web::HttpResponse::Ok()
+        .content_type("text/plain; charset=utf-8")
+        .body(leptos_bplate::content::sitemap::robots(&config))
+}
+
+/// Query of [`og_image`]
+#[cfg(feature = "ssr")]
+#[derive(serde::Deserialize)]
+struct OgImageQuery {
+    preview: Option<String>,
+}
+
+/// Open Graph card of a post, rendered on first request. Unpublished posts
+/// need the same `preview` token as their page.
+#[cfg(feature = "ssr")]
+#[actix_web::get("og/{slug}.png")]
+async fn og_image(
+    config: actix_web::web::Data<leptos_bplate::config::SiteConfig>,
+    store: actix_web::web::Data<leptos_bplate::content::ContentStore>,
+    slug: actix_web::web::Path<String>,
+    query: actix_web::web::Query<OgImageQuery>,
+) -> actix_web::Result<actix_web::HttpResponse> {
+    use leptos_bplate::content::og::og_card;
+    use leptos_bplate::content::preview::{preview_secret, verify_preview};
+
+    let now = chrono::Utc::now();
+    let Some(post) = store.get(&slug) else {
+        return Ok(actix_web::HttpResponse::NotFound().finish());
+    };
+    let published = post.meta.is_published(now);
+    let previewed = match (preview_secret(), query.preview.as_deref()) {
+        (Some(secret), Some(token)) => verify_preview(&secret, &slug, token, now),
+        _ => false,
+    };
+    if !published && !previewed {
+        return Ok(actix_web::HttpResponse::NotFound().finish());
+    }
+
+    let config = config.into_inner();
+    let png = actix_web::web::block(move || og_card(&post.meta, &config)).await??;
+    // Cards of unpublished posts shouldn't outlive their preview token in
+    // shared caches
+    let cache_control = match published {
+        true => "public, max-age=86400",
+        false => "private, no-store",
+    };
+    Ok(actix_web::HttpResponse::Ok()
+        .content_type("image/png")
+        .insert_header(("Cache-Control", cache_control))
+        .body(png))
+}
+
+/// Whether `request` may see the content diagnostics: always in debug builds,
+/// otherwise with the `[diagnostics] token` as bearer token or a valid admin
+/// session
+#[cfg(feature = "ssr")]
+fn diagnostics_allowed(
+    request: &actix_web::HttpRequest,
+    config: &leptos_bplate::config::SiteConfig,
+) -> bool {
+    use leptos_bplate::content::auth::{verify_session, SESSION_COOKIE};
+    use sha2::{Digest, Sha256};
+
+    if cfg!(debug_assertions) {
+        return true;
+    }
+    let bearer = request
+        .headers()
+        .get(actix_web::http::header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    // Comparing hashes keeps the comparison time independent of the token
+    let token_ok = match (&config.diagnostics.token, bearer) {
+        (Some(token), Some(bearer)) => Sha256::digest(token) == Sha256::digest(bearer),
+        _ => false,
+    };
+    let session_ok = config.admin.password_hash.is_some()
+        && request.cookie(SESSION_COOKIE).is_some_and(|cookie| {
+            verify_session(&config.admin.username, cookie.value(), chrono::Utc::now())
+        });
+    token_ok || session_ok
+}
+
+/// Lists content entries that failed to parse and were left out of the
+/// blog or the pages. Page entries are prefixed with `pages/`. See
+/// [`diagnostics_allowed`] for who gets to see them.
+#[cfg(feature = "ssr")]
+#[actix_web::get("diagnostics/content")]
+async fn content_diagnostics(
+    request: actix_web::HttpRequest,
+    config: actix_web::web::Data<leptos_bplate::config::SiteConfig>,
+    store: actix_web::web::Data<leptos_bplate::content::ContentStore>,
+    pages: actix_web::web::Data<leptos_bplate::content::PageStore>,
+) -> actix_web::HttpResponse {
+    if !diagnostics_allowed(&request, &config) {
+        return actix_web::HttpResponse::Unauthorized().finish();
+    }
+
+    let mut errors = store.errors();
+    errors.extend(pages.errors().into_iter().map(|mut diagnostic| {
+        diagnostic.entry = format!("pages/{}", diagnostic.entry);
+        diagnostic
+    }));
+    actix_web::HttpResponse::Ok().json(errors)
+}
+
 #[cfg(not(any(feature = "ssr", feature = "csr")))]
 pub fn main() {
     // no client-side main function
@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::blog::BlogPostMeta;
+
+/// Whether and where a post is visible to readers
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PostStatus {
+    /// Listed on the blog
+    Published,
+    /// Served by slug but left out of listings
+    Unlisted,
+    /// Goes live at its `publish_at`
+    Scheduled,
+    /// Hidden until `draft` is removed
+    Draft,
+}
+
+impl PostStatus {
+    pub fn label(self) -> &'static str {
+        match self {
+            PostStatus::Published => "Published",
+            PostStatus::Unlisted => "Unlisted",
+            PostStatus::Scheduled => "Scheduled",
+            PostStatus::Draft => "Draft",
+        }
+    }
+}
+
+/// An entry of the content source as listed in the admin area
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AdminEntry {
+    /// Id of the entry in the content source, e.g. its file name
+    pub id: String,
+    /// The post the entry provides, `None` if it was rejected
+    pub meta: Option<BlogPostMeta>,
+    pub status: Option<PostStatus>,
+    /// Why the entry was rejected
+    pub error: Option<String>,
+}
@@ -0,0 +1,77 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::blog::{slugify, BlogPostMeta};
+
+/// A link on an author's profile, e.g. their GitHub or website
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AuthorLink {
+    pub label: String,
+    pub url: String,
+}
+
+/// An entry of the author registry in `content/authors.yaml`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Author {
+    /// Identifier used in post frontmatter and URLs
+    pub id: String,
+    /// Display name
+    pub name: String,
+    /// Short bio shown on the author card and page
+    #[serde(default)]
+    pub bio: String,
+    /// Avatar image, an absolute URL or a path relative to /assets
+    #[serde(default)]
+    pub avatar: Option<String>,
+    #[serde(default)]
+    pub links: Vec<AuthorLink>,
+}
+
+impl Author {
+    /// Stand-in for sites without a registry: the name as written in the
+    /// frontmatter, with its slug as id
+    pub fn named(name: &str) -> Self {
+        Self {
+            id: slugify(name),
+            name: name.to_string(),
+            bio: String::new(),
+            avatar: None,
+            links: Vec::new(),
+        }
+    }
+
+    /// Root-relative URL of the author's page
+    pub fn href(&self) -> String {
+        format!("/blog/author/{}", self.id)
+    }
+
+    /// URL of the avatar image, if there is one
+    pub fn avatar_url(&self) -> Option<String> {
+        self.avatar.as_ref().map(|avatar| {
+            if avatar.starts_with("http://")
+                || avatar.starts_with("https://")
+                || avatar.starts_with('/')
+            {
+                avatar.clone()
+            } else {
+                format!("/assets/{}", avatar)
+            }
+        })
+    }
+
+    /// Up to two initials, shown when there's no avatar
+    pub fn initials(&self) -> String {
+        self.name
+            .split_whitespace()
+            .filter_map(|word| word.chars().next())
+            .take(2)
+            .flat_map(char::to_uppercase)
+            .collect()
+    }
+}
+
+/// Posts listed under a single author
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AuthorListing {
+    pub author: Author,
+    pub posts: Vec<BlogPostMeta>,
+}
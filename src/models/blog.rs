@@ -1,5 +1,9 @@
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::models::author::Author;
+use crate::models::image::ResponsiveImage;
+
 /// Metadata for a blog post, used for listing
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct BlogPostMeta {
@@ -7,14 +11,53 @@ pub struct BlogPostMeta {
     pub slug: String,
     /// Title of the blog post
     pub title: String,
-    /// Short description/excerpt
+    /// Short description; falls back to the automatic excerpt when left out
+    #[serde(default)]
     pub description: String,
-    /// Author name
+    /// Id of the author in the registry, defaults to the site's default author
+    #[serde(default)]
     pub author: String,
-    /// Publication date in ISO 8601 format (YYYY-MM-DD)
-    pub date: String,
+    /// Ids of co-authors, credited after `author`
+    #[serde(default)]
+    pub authors: Vec<String>,
+    /// `author` followed by `authors`, resolved against the registry while parsing
+    #[serde(default)]
+    pub author_profiles: Vec<Author>,
+    /// Publication date, written as YYYY-MM-DD in frontmatter
+    pub date: NaiveDate,
+    /// When the post was last updated, either YYYY-MM-DD or a full RFC 3339 timestamp
+    #[serde(default, with = "date_or_datetime")]
+    pub updated: Option<DateTime<Utc>>,
+    /// Drafts are hidden everywhere and can only be opened with a preview token
+    #[serde(default)]
+    pub draft: bool,
+    /// Scheduled publication time; the post stays hidden until then
+    #[serde(default, with = "date_or_datetime")]
+    pub publish_at: Option<DateTime<Utc>>,
+    /// Unlisted posts are reachable by slug but left out of listings
+    #[serde(default)]
+    pub unlisted: bool,
+    /// Plain text of the first paragraph, computed while parsing
+    #[serde(default)]
+    pub excerpt: String,
+    /// Number of words in the post body, computed while parsing
+    #[serde(default)]
+    pub word_count: usize,
+    /// Estimated reading time in minutes, computed while parsing
+    #[serde(default)]
+    pub reading_time: u32,
+    /// Name of the series the post is a part of
+    #[serde(default)]
+    pub series: Option<String>,
+    /// Position within the series, lowest first; parts without one come
+    /// last, by date
+    #[serde(default)]
+    pub series_order: Option<u32>,
     /// Optional cover image path (relative to /assets)
     pub cover_image: Option<String>,
+    /// Variants of `cover_image` generated while loading, if it could be processed
+    #[serde(default)]
+    pub cover: Option<ResponsiveImage>,
     /// Tags for categorization
     pub tags: Vec<String>,
     /// OpenGraph title (defaults to title if not specified)
@@ -31,6 +74,170 @@ pub struct BlogPostMeta {
     pub og_type: Option<String>,
 }
 
+impl BlogPostMeta {
+    /// Whether the post is tagged with something that normalizes to `tag_slug`
+    pub fn has_tag(&self, tag_slug: &str) -> bool {
+        self.tags.iter().any(|t| slugify(t) == tag_slug)
+    }
+
+    /// Whether `author_id` wrote or co-wrote the post
+    pub fn has_author(&self, author_id: &str) -> bool {
+        self.author_profiles.iter().any(|a| a.id == author_id)
+    }
+
+    /// Display names of everyone credited, or the raw `author` before the
+    /// post went through the store
+    pub fn author_names(&self) -> Vec<String> {
+        if self.author_profiles.is_empty() {
+            return vec![self.author.clone()];
+        }
+        self.author_profiles.iter().map(|a| a.name.clone()).collect()
+    }
+
+    /// Normalized series name used in URLs
+    pub fn series_slug(&self) -> Option<String> {
+        self.series.as_deref().map(slugify).filter(|slug| !slug.is_empty())
+    }
+
+    /// Whether the post may be served by slug at `now`
+    pub fn is_published(&self, now: DateTime<Utc>) -> bool {
+        !self.draft && self.publish_at.is_none_or(|at| at <= now)
+    }
+
+    /// When the post went (or goes) live: `publish_at` if set, otherwise
+    /// midnight UTC of `date`
+    pub fn published_at(&self) -> DateTime<Utc> {
+        self.publish_at
+            .unwrap_or_else(|| self.date.and_time(chrono::NaiveTime::MIN).and_utc())
+    }
+
+    /// Whether the post shows up in listings at `now`
+    pub fn is_listed(&self, now: DateTime<Utc>) -> bool {
+        self.is_published(now) && !self.unlisted
+    }
+}
+
+/// A tag together with how many listed posts use it
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TagSummary {
+    /// Tag as written in the first post that uses it
+    pub name: String,
+    /// Normalized form used in URLs
+    pub slug: String,
+    /// Number of listed posts with this tag
+    pub count: usize,
+}
+
+/// Posts listed under a single tag
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TagListing {
+    pub tag: TagSummary,
+    pub posts: Vec<BlogPostMeta>,
+}
+
+/// The listed parts of a series, in reading order
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SeriesListing {
+    /// Series name as written in the first part
+    pub name: String,
+    /// Normalized form used in URLs
+    pub slug: String,
+    pub posts: Vec<BlogPostMeta>,
+}
+
+impl SeriesListing {
+    /// Orders `posts` by `series_order`, then by publication date
+    pub fn new(name: String, slug: String, mut posts: Vec<BlogPostMeta>) -> Self {
+        posts.sort_by(|a, b| {
+            a.series_order
+                .unwrap_or(u32::MAX)
+                .cmp(&b.series_order.unwrap_or(u32::MAX))
+                .then_with(|| a.published_at().cmp(&b.published_at()))
+        });
+        Self { name, slug, posts }
+    }
+
+    /// Root-relative URL of the series overview
+    pub fn href(&self) -> String {
+        format!("/blog/series/{}", self.slug)
+    }
+
+    /// Index of the post with `slug` among the parts
+    pub fn position(&self, slug: &str) -> Option<usize> {
+        self.posts.iter().position(|post| post.slug == slug)
+    }
+}
+
+/// Posts to read after the current one
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct PostNeighbors {
+    /// The listed post published just before
+    pub prev: Option<BlogPostMeta>,
+    /// The listed post published just after
+    pub next: Option<BlogPostMeta>,
+    /// Listed posts on similar topics, most related first
+    pub related: Vec<BlogPostMeta>,
+}
+
+/// One page of the post listing
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PostListPage {
+    pub posts: Vec<BlogPostMeta>,
+    /// 1-based number of this page
+    pub page: usize,
+    pub per_page: usize,
+    pub total_posts: usize,
+    pub total_pages: usize,
+    /// Page to request for the previous set of posts
+    pub prev_page: Option<usize>,
+    /// Page to request for the next set of posts
+    pub next_page: Option<usize>,
+}
+
+impl PostListPage {
+    /// Cuts page `page` (1-based) of `per_page` posts out of `posts`
+    pub fn paginate(posts: Vec<BlogPostMeta>, page: usize, per_page: usize) -> Self {
+        let per_page = per_page.max(1);
+        let page = page.max(1);
+        let total_posts = posts.len();
+        let total_pages = total_posts.div_ceil(per_page);
+
+        Self {
+            posts: posts
+                .into_iter()
+                .skip((page - 1).saturating_mul(per_page))
+                .take(per_page)
+                .collect(),
+            page,
+            per_page,
+            total_posts,
+            total_pages,
+            prev_page: (page > 1).then(|| (page - 1).min(total_pages.max(1))),
+            next_page: (page < total_pages).then(|| page + 1),
+        }
+    }
+
+    /// Path of listing page `page`, with page 1 living at `/blog`
+    pub fn href(page: usize) -> String {
+        if page <= 1 {
+            "/blog".to_string()
+        } else {
+            format!("/blog/page/{}", page)
+        }
+    }
+}
+
+/// A heading in a post's table of contents
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TocEntry {
+    /// `id` of the heading element, usable as a `#fragment`
+    pub id: String,
+    /// Plain text of the heading
+    pub title: String,
+    /// Heading level, 2 for `h2` and so on
+    pub level: u8,
+}
+
 /// Full blog post including content
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct BlogPost {
@@ -39,4 +246,129 @@ pub struct BlogPost {
     pub meta: BlogPostMeta,
     /// Markdown content of the blog post
     pub content: String,
+    /// Headings of the post, in document order
+    #[serde(default)]
+    pub toc: Vec<TocEntry>,
+}
+
+/// Parses a frontmatter timestamp that is either a plain `YYYY-MM-DD` date
+/// (midnight UTC) or a full RFC 3339 date-time.
+pub fn parse_date_or_datetime(value: &str) -> Option<DateTime<Utc>> {
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(value) {
+        return Some(datetime.with_timezone(&Utc));
+    }
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|datetime| datetime.and_utc())
+}
+
+/// Serde helper for optional fields parsed with [`parse_date_or_datetime`]
+pub mod date_or_datetime {
+    use chrono::{DateTime, Utc};
+    use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        value: &Option<DateTime<Utc>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        value.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<DateTime<Utc>>, D::Error> {
+        match Option::<String>::deserialize(deserializer)? {
+            Some(value) => super::parse_date_or_datetime(&value)
+                .map(Some)
+                .ok_or_else(|| D::Error::custom(format!("invalid date: {}", value))),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Lowercases `text` and joins its alphanumeric runs with `-`, so
+/// `"Web Assembly"`, `"web-assembly"` and `"WEB_ASSEMBLY"` all become `"web-assembly"`.
+pub fn slugify(text: &str) -> String {
+    let mut slug = String::with_capacity(text.len());
+    for c in text.chars().flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    if slug.ends_with('-') {
+        slug.pop();
+    }
+    slug
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn posts(count: usize) -> Vec<BlogPostMeta> {
+        (0..count)
+            .map(|i| {
+                serde_json::from_value(serde_json::json!({
+                    "slug": format!("post-{}", i),
+                    "title": format!("Post {}", i),
+                    "date": "2024-01-01",
+                    "tags": [],
+                }))
+                .unwrap()
+            })
+            .collect()
+    }
+
+    fn slugs(page: &PostListPage) -> Vec<&str> {
+        page.posts.iter().map(|post| post.slug.as_str()).collect()
+    }
+
+    #[test]
+    fn paginates_posts() {
+        let first = PostListPage::paginate(posts(5), 1, 2);
+        assert_eq!(slugs(&first), ["post-0", "post-1"]);
+        assert_eq!((first.total_posts, first.total_pages), (5, 3));
+        assert_eq!((first.prev_page, first.next_page), (None, Some(2)));
+
+        let last = PostListPage::paginate(posts(5), 3, 2);
+        assert_eq!(slugs(&last), ["post-4"]);
+        assert_eq!((last.prev_page, last.next_page), (Some(2), None));
+    }
+
+    #[test]
+    fn clamps_page_and_per_page() {
+        let page = PostListPage::paginate(posts(3), 0, 0);
+        assert_eq!((page.page, page.per_page), (1, 1));
+        assert_eq!(slugs(&page), ["post-0"]);
+        assert_eq!(page.total_pages, 3);
+    }
+
+    #[test]
+    fn links_back_from_pages_past_the_end() {
+        let page = PostListPage::paginate(posts(3), 10, 2);
+        assert!(page.posts.is_empty());
+        assert_eq!((page.prev_page, page.next_page), (Some(2), None));
+
+        let page = PostListPage::paginate(posts(3), usize::MAX, usize::MAX);
+        assert!(page.posts.is_empty());
+        assert_eq!(page.next_page, None);
+
+        let empty = PostListPage::paginate(Vec::new(), 2, 10);
+        assert_eq!(empty.total_pages, 0);
+        assert_eq!((empty.prev_page, empty.next_page), (Some(1), None));
+    }
+
+    #[test]
+    fn slugifies_tags() {
+        assert_eq!(slugify("Web Assembly"), "web-assembly");
+        assert_eq!(slugify("WEB_ASSEMBLY"), "web-assembly");
+        assert_eq!(slugify("  --C++ & Rust!-- "), "c-rust");
+        assert_eq!(slugify("Ünïcödé Straße"), "ünïcödé-straße");
+        assert_eq!(slugify("日本語"), "日本語");
+        assert_eq!(slugify(""), "");
+        assert_eq!(slugify("#!?"), "");
+    }
 }
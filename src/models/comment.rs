@@ -0,0 +1,22 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// An approved comment under a post, with the approved replies to it
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Comment {
+    pub id: i64,
+    /// Name the commenter gave
+    pub author: String,
+    /// Comment body rendered from markdown and sanitized
+    pub html: String,
+    pub created_at: DateTime<Utc>,
+    /// Replies, oldest first
+    pub replies: Vec<Comment>,
+}
+
+impl Comment {
+    /// This comment plus all replies below it
+    pub fn count(&self) -> usize {
+        1 + self.replies.iter().map(Comment::count).sum::<usize>()
+    }
+}
@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+
+/// Resized variants of an image in one format
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ImageSource {
+    /// MIME type, e.g. `image/avif`
+    pub mime: String,
+    /// `srcset` listing every width, e.g. `/assets/img/cover-1a2b-480.avif 480w, ...`
+    pub srcset: String,
+}
+
+/// An image with the variants generated by the image pipeline, rendered as
+/// a `<picture>`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ResponsiveImage {
+    /// URL of the original, the fallback for browsers without `<picture>`
+    pub src: String,
+    /// Intrinsic width of the original in pixels
+    pub width: u32,
+    /// Intrinsic height of the original in pixels
+    pub height: u32,
+    /// Tiny blurred version as a `data:` URL, shown while the image loads
+    pub placeholder: String,
+    /// Variants in order of preference
+    pub sources: Vec<ImageSource>,
+    /// `sizes` attribute telling the browser which width to pick
+    pub sizes: String,
+}
+
+impl ResponsiveImage {
+    /// Inline style that shows the placeholder behind the image until it loads
+    pub fn placeholder_style(&self) -> String {
+        format!(
+            "background-size:cover;background-position:center;background-image:url({})",
+            self.placeholder
+        )
+    }
+}
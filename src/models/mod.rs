@@ -1 +1,7 @@
+pub mod admin;
+pub mod author;
 pub mod blog;
+pub mod comment;
+pub mod image;
+pub mod page;
+pub mod search;
@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+
+/// Where a page links itself from
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum NavPlacement {
+    /// Between the header links and the call to action
+    Header,
+    /// Next to the copyright notice in the footer
+    Footer,
+}
+
+/// Page frontmatter
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PageMeta {
+    pub title: String,
+    /// Meta description for search engines and link previews
+    #[serde(default)]
+    pub description: String,
+    /// URL path without leading slash, e.g. `legal/privacy`. Defaults to the file name.
+    #[serde(default)]
+    pub path: String,
+    /// Adds a link to the page to the header or footer
+    #[serde(default)]
+    pub nav: Option<NavPlacement>,
+    /// Link text in the navigation, defaults to the title
+    #[serde(default)]
+    pub nav_label: Option<String>,
+    /// Position among the other page links, lowest first
+    #[serde(default)]
+    pub nav_order: i32,
+}
+
+impl PageMeta {
+    /// Root-relative URL of the page
+    pub fn href(&self) -> String {
+        format!("/{}", self.path)
+    }
+}
+
+/// A standalone markdown page such as About or Privacy
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Page {
+    #[serde(flatten)]
+    pub meta: PageMeta,
+    /// Rendered HTML
+    pub content: String,
+}
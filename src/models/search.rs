@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::blog::BlogPostMeta;
+
+/// A post matching a search query
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SearchHit {
+    pub meta: BlogPostMeta,
+    /// Excerpt around the first match, HTML escaped with matches wrapped in `<mark>`
+    pub snippet: String,
+    /// Relevance, higher is better
+    pub score: f32,
+}
@@ -0,0 +1,316 @@
+use std::time::Duration;
+
+use crate::components::blog::{BlogPostContent, BlogPostError, BlogPostLoading};
+use crate::components::Layout;
+use crate::models::admin::{AdminEntry, PostStatus};
+use crate::server_functions::admin::{
+    admin_session, fetch_admin_entries, fetch_admin_entry, preview_admin_entry, AdminLogin,
+    AdminLogout, DeleteAdminEntry, SaveAdminEntry,
+};
+use crate::server_functions::error_message;
+use leptos::prelude::*;
+use leptos::{component, IntoView};
+use leptos_meta::{Meta, Title};
+use leptos_router::hooks::{use_navigate, use_query_map};
+use leptos_router::location::Url;
+use leptos_router::NavigateOptions;
+
+/// Path the admin area lives under. Left out of the sitemap and static exports.
+pub const ADMIN_PATH: &str = "/admin";
+
+/// How long typing has to pause before the preview updates
+const PREVIEW_DEBOUNCE: Duration = Duration::from_millis(400);
+
+/// Every post in the content source, at `/admin`
+#[component]
+pub fn AdminPage() -> impl IntoView {
+    view! {
+        <Title text="Admin" />
+        <AdminShell>
+            <AdminEntries />
+        </AdminShell>
+    }
+}
+
+/// Markdown editor with a live preview, at `/admin/edit?id=<entry>`, or for a
+/// new post at `/admin/edit`
+#[component]
+pub fn AdminEditorPage() -> impl IntoView {
+    view! {
+        <Title text="Edit post" />
+        <AdminShell>
+            <EditorLoader />
+        </AdminShell>
+    }
+}
+
+/// Layout of the admin pages: the login form, or the admin navigation
+/// followed by `children` once logged in
+#[component]
+fn AdminShell(children: ChildrenFn) -> impl IntoView {
+    let login = ServerAction::<AdminLogin>::new();
+    let logout = ServerAction::<AdminLogout>::new();
+    let session = Resource::new(
+        move || (login.version().get(), logout.version().get()),
+        |_| admin_session(),
+    );
+
+    view! {
+        <Meta name="robots" content="noindex" />
+        <Layout>
+            <div class="pt-24 pb-20 px-4 sm:px-6 lg:px-8">
+                <div class="max-w-7xl mx-auto">
+                    <Suspense fallback=move || view! { <BlogPostLoading /> }>
+                        {move || match session.get() {
+                            Some(Ok(true)) => view! {
+                                <div class="flex items-center justify-between gap-4 mb-10">
+                                    <nav class="flex gap-6 font-semibold">
+                                        <a href=ADMIN_PATH class="text-gray-900 hover:text-blue-600 transition">"Posts"</a>
+                                        <a href=format!("{}/edit", ADMIN_PATH) class="text-gray-900 hover:text-blue-600 transition">"New post"</a>
+                                    </nav>
+                                    <ActionForm action=logout>
+                                        <button type="submit" class="text-sm text-gray-600 hover:text-gray-900 transition">
+                                            "Log out"
+                                        </button>
+                                    </ActionForm>
+                                </div>
+                                {children()}
+                            }.into_any(),
+                            Some(Ok(false)) => view! { <LoginForm action=login /> }.into_any(),
+                            Some(Err(e)) => view! { <BlogPostError error=error_message(&e) /> }.into_any(),
+                            None => view! { <BlogPostLoading /> }.into_any(),
+                        }}
+                    </Suspense>
+                </div>
+            </div>
+        </Layout>
+    }
+}
+
+#[component]
+fn LoginForm(action: ServerAction<AdminLogin>) -> impl IntoView {
+    let input_class = "w-full px-4 py-2 border border-gray-300 rounded-lg focus:outline-none focus:ring-2 focus:ring-blue-500";
+
+    view! {
+        <div class="max-w-sm mx-auto">
+            <h1 class="text-3xl font-bold text-gray-900 mb-6">"Log in"</h1>
+            <ActionForm action=action attr:class="space-y-4">
+                <input type="text" name="username" required autocomplete="username" placeholder="Username" class=input_class />
+                <input type="password" name="password" required autocomplete="current-password" placeholder="Password" class=input_class />
+                <button
+                    type="submit"
+                    disabled=move || action.pending().get()
+                    class="w-full px-5 py-2 bg-blue-600 text-white font-semibold rounded-lg hover:bg-blue-700 transition disabled:opacity-50"
+                >
+                    "Log in"
+                </button>
+                {move || action.value().get().and_then(Result::err).map(|e| view! {
+                    <p class="text-sm text-red-600">{error_message(&e)}</p>
+                })}
+            </ActionForm>
+        </div>
+    }
+}
+
+/// Table of every entry, broken ones first
+#[component]
+fn AdminEntries() -> impl IntoView {
+    let delete = ServerAction::<DeleteAdminEntry>::new();
+    let entries = Resource::new(move || delete.version().get(), |_| fetch_admin_entries());
+
+    view! {
+        <h1 class="text-4xl font-bold text-gray-900 mb-8">"Posts"</h1>
+        {move || delete.value().get().and_then(Result::err).map(|e| view! {
+            <p class="mb-6 text-red-600">{error_message(&e)}</p>
+        })}
+        <Transition fallback=move || view! { <BlogPostLoading /> }>
+            {move || entries.get().map(|entries| match entries {
+                Ok(entries) if entries.is_empty() => view! {
+                    <p class="text-gray-500">"No posts yet."</p>
+                }.into_any(),
+                Ok(entries) => view! {
+                    <div class="divide-y divide-gray-200 border border-gray-200 rounded-2xl">
+                        {entries.into_iter().map(|entry| view! {
+                            <AdminEntryRow entry=entry delete=delete />
+                        }).collect::<Vec<_>>()}
+                    </div>
+                }.into_any(),
+                Err(e) => view! { <BlogPostError error=error_message(&e) /> }.into_any(),
+            })}
+        </Transition>
+    }
+}
+
+#[component]
+fn AdminEntryRow(entry: AdminEntry, delete: ServerAction<DeleteAdminEntry>) -> impl IntoView {
+    // Deleting takes a second click
+    let confirming = RwSignal::new(false);
+    let edit_href = format!("{}/edit?id={}", ADMIN_PATH, Url::escape(&entry.id));
+    let (badge, badge_class) = match (&entry.status, &entry.error) {
+        (_, Some(_)) => ("Error", "bg-red-100 text-red-700"),
+        (Some(PostStatus::Published), _) => ("Published", "bg-green-100 text-green-700"),
+        (Some(status), _) => (status.label(), "bg-gray-100 text-gray-700"),
+        (None, None) => ("Unknown", "bg-gray-100 text-gray-700"),
+    };
+    let id = entry.id.clone();
+
+    view! {
+        <div class="flex flex-col md:flex-row md:items-center gap-4 p-4">
+            <div class="min-w-0 flex-1">
+                <div class="flex items-center gap-3">
+                    <span class=format!("px-2 py-0.5 text-xs font-semibold rounded-full {}", badge_class)>{badge}</span>
+                    <a href=edit_href.clone() class="font-semibold text-gray-900 hover:text-blue-600 transition truncate">
+                        {entry.meta.as_ref().map(|meta| meta.title.clone()).unwrap_or_else(|| entry.id.clone())}
+                    </a>
+                </div>
+                <p class="text-sm text-gray-500 mt-1">
+                    {entry.id.clone()}
+                    {entry.meta.as_ref().map(|meta| format!(" · {}", meta.date))}
+                </p>
+                {entry.error.map(|error| view! { <p class="text-sm text-red-600 mt-1">{error}</p> })}
+            </div>
+            <div class="flex gap-4 text-sm font-semibold">
+                {entry.meta.as_ref().filter(|_| entry.status != Some(PostStatus::Draft)).map(|meta| view! {
+                    <a href=format!("/blog/{}", meta.slug) class="text-gray-600 hover:text-gray-900 transition">"View"</a>
+                })}
+                <a href=edit_href class="text-blue-600 hover:text-blue-700 transition">"Edit"</a>
+                <button
+                    type="button"
+                    class="text-red-600 hover:text-red-700 transition"
+                    on:click=move |_| {
+                        if confirming.get_untracked() {
+                            delete.dispatch(DeleteAdminEntry { id: id.clone() });
+                        } else {
+                            confirming.set(true);
+                        }
+                    }
+                >
+                    {move || if confirming.get() { "Confirm delete" } else { "Delete" }}
+                </button>
+            </div>
+        </div>
+    }
+}
+
+/// Loads the entry named in the query, or a template for a new post
+#[component]
+fn EditorLoader() -> impl IntoView {
+    let query = use_query_map();
+    let id = move || query.read().get("id");
+    let content = Resource::new(id, |id| async move {
+        match id {
+            Some(id) => fetch_admin_entry(id).await,
+            None => Ok(new_post_template(chrono::Utc::now().date_naive())),
+        }
+    });
+
+    view! {
+        <Suspense fallback=move || view! { <BlogPostLoading /> }>
+            {move || content.get().map(|content| match content {
+                Ok(content) => view! { <AdminEditor id=id() content=content /> }.into_any(),
+                Err(e) => view! { <BlogPostError error=error_message(&e) /> }.into_any(),
+            })}
+        </Suspense>
+    }
+}
+
+/// Frontmatter and placeholder text new posts start out with
+fn new_post_template(date: chrono::NaiveDate) -> String {
+    format!(
+        "---\ntitle: \"Untitled\"\nslug: \"untitled\"\ndate: {}\ndescription: \"\"\ntags: []\ndraft: true\n---\n\nStart writing here.\n",
+        date
+    )
+}
+
+/// Side-by-side markdown editor and preview. The preview is rendered on the
+/// server by the same pipeline as saved posts, shortly after typing stops.
+#[component]
+fn AdminEditor(id: Option<String>, content: String) -> impl IntoView {
+    let draft = RwSignal::new(content.clone());
+    let rendered = RwSignal::new(content.clone());
+    let preview = Resource::new(move || rendered.get(), preview_admin_entry);
+    let save = ServerAction::<SaveAdminEntry>::new();
+
+    let pending = StoredValue::new(None::<TimeoutHandle>);
+    let on_input = move |ev| {
+        let value = event_target_value(&ev);
+        draft.set(value.clone());
+        if let Some(handle) = pending.get_value() {
+            handle.clear();
+        }
+        let handle = set_timeout_with_handle(move || rendered.set(value), PREVIEW_DEBOUNCE).ok();
+        pending.set_value(handle);
+    };
+
+    // A new post keeps being edited as the entry it was saved as
+    let navigate = use_navigate();
+    let is_new = id.is_none();
+    Effect::new(move |_| {
+        if let Some(Ok(saved)) = save.value().get() {
+            if is_new {
+                navigate(
+                    &format!("{}/edit?id={}", ADMIN_PATH, Url::escape(&saved)),
+                    NavigateOptions {
+                        replace: true,
+                        ..Default::default()
+                    },
+                );
+            }
+        }
+    });
+
+    let on_save = move |_| {
+        save.dispatch(SaveAdminEntry {
+            id: id.clone(),
+            content: draft.get_untracked(),
+        });
+    };
+
+    view! {
+        <div class="flex items-center justify-between gap-4 mb-6">
+            <h1 class="text-3xl font-bold text-gray-900">
+                {if is_new { "New post" } else { "Edit post" }}
+            </h1>
+            <div class="flex items-center gap-4">
+                {move || match save.value().get() {
+                    _ if save.pending().get() => view! { <span class="text-sm text-gray-500">"Saving…"</span> }.into_any(),
+                    Some(Ok(_)) => view! { <span class="text-sm text-green-700">"Saved"</span> }.into_any(),
+                    Some(Err(e)) => view! { <span class="text-sm text-red-600">{error_message(&e)}</span> }.into_any(),
+                    None => ().into_any(),
+                }}
+                <button
+                    type="button"
+                    on:click=on_save
+                    disabled=move || save.pending().get()
+                    class="px-5 py-2 bg-blue-600 text-white font-semibold rounded-lg hover:bg-blue-700 transition disabled:opacity-50"
+                >
+                    "Save"
+                </button>
+            </div>
+        </div>
+        <div class="grid lg:grid-cols-2 gap-8">
+            <textarea
+                on:input=on_input
+                spellcheck="false"
+                aria-label="Markdown"
+                class="w-full min-h-[70vh] p-4 font-mono text-sm border border-gray-300 rounded-lg focus:outline-none focus:ring-2 focus:ring-blue-500"
+            >
+                {content}
+            </textarea>
+            <div class="min-w-0 min-h-[70vh] p-6 border border-gray-200 rounded-lg overflow-auto">
+                <Transition fallback=move || view! { <BlogPostLoading /> }>
+                    {move || preview.get().map(|preview| match preview {
+                        Ok(post) => view! {
+                            <h1 class="text-3xl font-bold text-gray-900 mb-6">{post.meta.title}</h1>
+                            <BlogPostContent html_content=post.content />
+                        }.into_any(),
+                        Err(e) => view! {
+                            <p class="text-red-600">{error_message(&e)}</p>
+                        }.into_any(),
+                    })}
+                </Transition>
+            </div>
+        </div>
+    }
+    .into_any()
+}
@@ -0,0 +1,84 @@
+use crate::components::blog::{
+    AuthorAvatar, AuthorLinks, BlogPostCard, BlogPostError, BlogPostLoading,
+};
+use crate::components::structured_data::{self, JsonLd};
+use crate::components::Layout;
+use crate::config::use_site_config;
+use crate::server_functions::authors::fetch_author_posts;
+use leptos::prelude::*;
+use leptos::{component, IntoView};
+use leptos_meta::{Meta, Title};
+use leptos_router::hooks::use_params_map;
+
+/// An author's profile and the posts they wrote
+#[component]
+pub fn AuthorPage() -> impl IntoView {
+    let params = use_params_map();
+    let id = move || params.read().get("id").unwrap_or_default();
+
+    let listing = Resource::new(id, fetch_author_posts);
+    let config = use_site_config();
+
+    view! {
+        <Layout>
+            <div class="pt-24 pb-20 px-4 sm:px-6 lg:px-8">
+                <div class="max-w-5xl mx-auto">
+                    <Suspense fallback=move || view! { <BlogPostLoading /> }>
+                        {move || {
+                            match listing.get() {
+                                Some(Ok(Some(listing))) => view! {
+                                    <Title text=listing.author.name.clone() />
+                                    <JsonLd data=structured_data::person(&listing.author, &config) />
+                                    {(!listing.author.bio.is_empty()).then(|| view! {
+                                        <Meta name="description" content=listing.author.bio.clone() />
+                                    })}
+                                    <div class="mb-12">
+                                        <a href="/blog" class="text-blue-600 hover:text-blue-800 transition inline-flex text-left gap-2 mb-6">
+                                            <span>"← Back to Blog"</span>
+                                        </a>
+                                        <div class="flex items-center gap-6">
+                                            <AuthorAvatar author=listing.author.clone() class="w-20 h-20 text-2xl" />
+                                            <div>
+                                                <h1 class="text-5xl font-bold text-gray-900 mb-2">
+                                                    {listing.author.name.clone()}
+                                                </h1>
+                                                <p class="text-gray-600">
+                                                    {format!("{} posts", listing.posts.len())}
+                                                </p>
+                                            </div>
+                                        </div>
+                                        {(!listing.author.bio.is_empty()).then(|| view! {
+                                            <p class="text-xl text-gray-600 mt-6">
+                                                {listing.author.bio.clone()}
+                                            </p>
+                                        })}
+                                        <AuthorLinks links=listing.author.links.clone() />
+                                    </div>
+                                    <div class="space-y-8">
+                                        {listing.posts.into_iter().map(|post| {
+                                            view! { <BlogPostCard post=post /> }
+                                        }).collect::<Vec<_>>()}
+                                    </div>
+                                }.into_any(),
+                                Some(Ok(None)) => view! {
+                                    <div class="text-center py-12">
+                                        <h1 class="text-3xl font-bold text-gray-900 mb-4">
+                                            "Author Not Found"
+                                        </h1>
+                                        <a href="/blog" class="text-blue-600 hover:text-blue-800 transition">
+                                            "← Back to Blog"
+                                        </a>
+                                    </div>
+                                }.into_any(),
+                                Some(Err(e)) => view! {
+                                    <BlogPostError error=format!("Error loading posts: {}", e) />
+                                }.into_any(),
+                                None => view! { <BlogPostLoading /> }.into_any(),
+                            }
+                        }}
+                    </Suspense>
+                </div>
+            </div>
+        </Layout>
+    }
+}
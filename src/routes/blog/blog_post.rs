@@ -1,36 +1,89 @@
 use crate::components::blog::{
-    BlogPostContent, BlogPostError, BlogPostHeader, BlogPostLoading, BlogPostMetaTags,
-    BlogPostNotFound,
+    AuthorCard, BlogPostContent, BlogPostError, BlogPostNeighbors, BlogPostHeader, BlogPostLoading, BlogPostMetaTags,
+    BlogPostNotFound, BlogPostPreviewBanner, BlogPostToc, SeriesBox, SeriesNav,
 };
+use crate::components::comments::Comments;
 use crate::components::Layout;
-use crate::server_functions::posts::fetch_blog_post;
+use crate::config::use_site_config;
+use crate::server_functions::posts::{fetch_blog_post, fetch_post_neighbors};
+use crate::server_functions::series::fetch_series;
 use leptos::prelude::*;
 use leptos::{component, IntoView};
-use leptos_router::hooks::use_params_map;
+use leptos_meta::Meta;
+use leptos_router::hooks::{use_params_map, use_query_map};
 
 /// Main blog post page component
 #[component]
 pub fn BlogPostPage() -> impl IntoView {
     let params = use_params_map();
+    let query = use_query_map();
     let slug = move || params.read().get("slug").unwrap_or_default();
+    let preview = move || query.read().get("preview");
 
-    let post = Resource::new(move || slug(), |slug| fetch_blog_post(slug));
+    let post = Resource::new(
+        move || (slug(), preview()),
+        |(slug, preview)| fetch_blog_post(slug, preview),
+    );
+    let series = Resource::new(
+        move || {
+            post.get()
+                .and_then(|post| post.ok().flatten())
+                .and_then(|post| post.meta.series_slug())
+        },
+        |series| async move {
+            match series {
+                Some(series) => fetch_series(series).await,
+                None => Ok(None),
+            }
+        },
+    );
+    let neighbors = Resource::new(slug, fetch_post_neighbors);
+    let series_listing = move || series.get().and_then(|listing| listing.ok().flatten());
+    let comments_enabled = use_site_config().comments.enabled;
 
     view! {
         <Layout>
             <div class="pt-24 pb-20 px-4 sm:px-6 lg:px-8">
-                <div class="max-w-3xl mx-auto">
+                <div class="max-w-3xl lg:max-w-6xl mx-auto">
                     <Suspense fallback=move || view! { <BlogPostLoading /> }>
                         {move || {
                             match post.get() {
                                 Some(Ok(Some(blog_post))) => {
+                                    let hidden = blog_post.meta.draft
+                                        || blog_post.meta.unlisted
+                                        || preview().is_some();
+
                                     view! {
-                                        <BlogPostMetaTags post=blog_post.clone() />
+                                        <BlogPostMetaTags post=blog_post.clone() preview=preview() />
+                                        {hidden.then(|| view! { <Meta name="robots" content="noindex" /> })}
+                                        {preview().is_some().then(|| view! { <BlogPostPreviewBanner /> })}
 
-                                        <article>
-                                            <BlogPostHeader post=blog_post.clone() />
-                                            <BlogPostContent html_content=blog_post.content.clone() />
-                                        </article>
+                                        <div class="lg:grid lg:grid-cols-[minmax(0,1fr)_14rem] lg:gap-12">
+                                            <article class="min-w-0">
+                                                <BlogPostHeader post=blog_post.clone() />
+                                                {move || series_listing().map(|listing| view! {
+                                                    <SeriesBox listing=listing current=slug() />
+                                                }.into_any())}
+                                                <BlogPostContent html_content=blog_post.content.clone() />
+                                                {move || series_listing().map(|listing| view! {
+                                                    <SeriesNav listing=listing current=slug() />
+                                                }.into_any())}
+                                                <div class="mt-16 space-y-4">
+                                                    {blog_post.meta.author_profiles.iter().map(|author| {
+                                                        view! { <AuthorCard author=author.clone() /> }
+                                                    }).collect::<Vec<_>>()}
+                                                </div>
+                                                {move || neighbors.get().and_then(Result::ok).map(|neighbors| view! {
+                                                    <BlogPostNeighbors neighbors=neighbors />
+                                                }.into_any())}
+                                                {(comments_enabled && preview().is_none()).then(|| view! {
+                                                    <Comments slug=blog_post.meta.slug.clone() />
+                                                }.into_any())}
+                                            </article>
+                                            {(!blog_post.toc.is_empty()).then(|| view! {
+                                                <BlogPostToc entries=blog_post.toc.clone() />
+                                            })}
+                                        </div>
                                     }.into_any()
                                 },
                                 Some(Ok(None)) => {
@@ -1,20 +1,41 @@
+use crate::components::blog::BlogPostCard;
 use crate::components::Layout;
-use crate::models::blog::BlogPostMeta;
+use crate::config::use_site_config;
+use crate::models::blog::PostListPage;
+use crate::server_functions::static_export::StaticExportClient;
+use leptos::server_fn::codec::{GetUrl, Json};
 use leptos::prelude::*;
 use leptos::{component, IntoView};
+use leptos_meta::Link;
+use leptos_router::hooks::use_params_map;
 
-#[cfg(feature = "ssr")]
-use crate::server_functions::posts::process_posts;
+/// Posts per listing page when the caller doesn't ask for a size
+pub const DEFAULT_PER_PAGE: usize = 10;
+/// Largest page size a caller may ask for
+pub const MAX_PER_PAGE: usize = 50;
 
-/// Fetches the list of blog posts from KV
-#[server(FetchBlogList, "/api")]
-pub async fn fetch_blog_list() -> Result<Vec<BlogPostMeta>, ServerFnError> {
+/// Fetches one page of the blog listing. `page` is 1-based and defaults to 1.
+#[server(
+    name = FetchBlogList,
+    prefix = "/api",
+    input = GetUrl,
+    output = Json,
+    client = StaticExportClient
+)]
+pub async fn fetch_blog_list(
+    page: Option<usize>,
+    per_page: Option<usize>,
+) -> Result<PostListPage, ServerFnError> {
     #[cfg(feature = "ssr")]
     {
-        let blog_path = "content/blog";
-        let posts = process_posts(blog_path);
-        let meta: Vec<BlogPostMeta> = posts.into_iter().map(|p| p.meta).collect();
-        Ok(meta)
+        let store = crate::content::use_content_store().await?;
+        let per_page = per_page.unwrap_or(DEFAULT_PER_PAGE).clamp(1, MAX_PER_PAGE);
+
+        Ok(PostListPage::paginate(
+            store.listed_metas(chrono::Utc::now()),
+            page.unwrap_or(1),
+            per_page,
+        ))
     }
 
     #[cfg(not(feature = "ssr"))]
@@ -25,9 +46,24 @@ pub async fn fetch_blog_list() -> Result<Vec<BlogPostMeta>, ServerFnError> {
     }
 }
 
+/// Blog listing, served at `/blog` for the first page and `/blog/page/:n` after that.
+/// Page numbers that don't parse or are past the last page answer with a 404.
 #[component]
 pub fn BlogListPage() -> impl IntoView {
-    let posts = Resource::new(|| (), |_| fetch_blog_list());
+    let config = use_site_config();
+    let params = use_params_map();
+    // `None` for a page number that isn't one
+    let page = move || match params.read().get("n") {
+        Some(n) => n.parse::<usize>().ok().filter(|n| *n > 0),
+        None => Some(1),
+    };
+
+    let posts = Resource::new(page, |page| async move {
+        match page {
+            Some(page) => fetch_blog_list(Some(page), None).await.map(Some),
+            None => Ok(None),
+        }
+    });
 
     view! {
         <Layout>
@@ -38,7 +74,7 @@ pub fn BlogListPage() -> impl IntoView {
                             "Blog"
                         </h1>
                         <p class="text-xl text-gray-600">
-                            "Thoughts, tutorials, and updates from our team."
+                            {config.description}
                         </p>
                     </div>
 
@@ -49,8 +85,8 @@ pub fn BlogListPage() -> impl IntoView {
                     }>
                         {move || {
                             match posts.get() {
-                                Some(Ok(blog_posts)) => {
-                                    if blog_posts.is_empty() {
+                                Some(Ok(Some(list))) if list.page <= list.total_pages.max(1) => {
+                                    if list.posts.is_empty() {
                                         view! {
                                             <div class="py-12">
                                                 <p class="text-gray-500 text-lg">
@@ -60,16 +96,38 @@ pub fn BlogListPage() -> impl IntoView {
                                         }.into_any()
                                     } else {
                                         view! {
+                                            {list.prev_page.map(|prev| view! {
+                                                <Link rel="prev" href=PostListPage::href(prev) />
+                                            })}
+                                            {list.next_page.map(|next| view! {
+                                                <Link rel="next" href=PostListPage::href(next) />
+                                            })}
                                             <div class="space-y-8">
-                                                {blog_posts.into_iter().map(|post| {
+                                                {list.posts.clone().into_iter().map(|post| {
                                                     view! {
                                                         <BlogPostCard post=post />
                                                     }
                                                 }).collect::<Vec<_>>()}
                                             </div>
+                                            <Pagination list=list />
                                         }.into_any()
                                     }
                                 },
+                                Some(Ok(_)) => {
+                                    #[cfg(feature = "ssr")]
+                                    expect_context::<leptos_actix::ResponseOptions>()
+                                        .set_status(actix_web::http::StatusCode::NOT_FOUND);
+                                    view! {
+                                        <div class="py-12">
+                                            <p class="text-gray-500 text-lg">
+                                                "There is no such page."
+                                            </p>
+                                            <a href="/blog" class="text-blue-600 hover:text-blue-800 transition">
+                                                "← Back to Blog"
+                                            </a>
+                                        </div>
+                                    }.into_any()
+                                },
                                 _ => view! {
                                     <div class="py-12">
                                         <p class="text-red-500 text-lg">
@@ -86,43 +144,30 @@ pub fn BlogListPage() -> impl IntoView {
     }
 }
 
+/// Previous/next links and page position below the listing
 #[component]
-fn BlogPostCard(post: BlogPostMeta) -> impl IntoView {
-    let href = format!("/blog/{}", post.slug);
-
-    view! {
-        <article class="bg-white border border-gray-200 rounded-2xl overflow-hidden shadow-all text-left">
-            <div class="p-6">
-                <a href=href class="group">
-                    <h2 class="text-2xl font-bold text-gray-900 group-hover:text-blue-600 transition mb-3">
-                        {post.title.clone()}
-                    </h2>
-
-                    <p class="text-gray-600 mb-4">
-                        {post.description.clone()}
-                    </p>
-                </a>
-                {post.cover_image.clone().map(|image| {
-                    view! {
-                        <div class="aspect-video overflow-hidden mb-4">
-                            <img
-                                src=format!("/assets/{}", image)
-                                alt=post.title.clone()
-                                class="w-full h-full object-cover"
-                            />
-                        </div>
-                    }
-                })}
-                <div class="flex flex-wrap gap-2">
-                    {post.tags.iter().map(|tag| {
-                        view! {
-                            <span class="px-3 py-1 bg-gray-100 text-gray-700 text-sm rounded-full">
-                                {tag.clone()}
-                            </span>
-                        }
-                    }).collect::<Vec<_>>()}
+fn Pagination(list: PostListPage) -> impl IntoView {
+    (list.total_pages > 1).then(|| {
+        view! {
+            <nav class="flex justify-between items-center mt-12 pt-8 border-t border-gray-200">
+                <div class="w-32">
+                    {list.prev_page.map(|prev| view! {
+                        <a href=PostListPage::href(prev) rel="prev" class="text-blue-600 hover:text-blue-800 transition">
+                            "← Previous"
+                        </a>
+                    })}
                 </div>
-            </div>
-        </article>
-    }
+                <span class="text-gray-500 text-sm">
+                    {format!("Page {} of {}", list.page, list.total_pages)}
+                </span>
+                <div class="w-32 text-right">
+                    {list.next_page.map(|next| view! {
+                        <a href=PostListPage::href(next) rel="next" class="text-blue-600 hover:text-blue-800 transition">
+                            "Next →"
+                        </a>
+                    })}
+                </div>
+            </nav>
+        }
+    })
 }
@@ -1,2 +1,5 @@
+pub mod authors;
 pub mod blog_post;
 pub mod blog_section;
+pub mod series;
+pub mod tags;
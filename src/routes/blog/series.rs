@@ -0,0 +1,74 @@
+use crate::components::blog::{BlogPostCard, BlogPostError, BlogPostLoading};
+use crate::components::Layout;
+use crate::server_functions::series::fetch_series;
+use leptos::prelude::*;
+use leptos::{component, IntoView};
+use leptos_meta::Title;
+use leptos_router::hooks::use_params_map;
+
+/// Every part of a series, in reading order
+#[component]
+pub fn SeriesPage() -> impl IntoView {
+    let params = use_params_map();
+    let name = move || params.read().get("name").unwrap_or_default();
+
+    let listing = Resource::new(name, fetch_series);
+
+    view! {
+        <Layout>
+            <div class="pt-24 pb-20 px-4 sm:px-6 lg:px-8">
+                <div class="max-w-5xl mx-auto">
+                    <Suspense fallback=move || view! { <BlogPostLoading /> }>
+                        {move || {
+                            match listing.get() {
+                                Some(Ok(Some(listing))) => view! {
+                                    <Title text=listing.name.clone() />
+                                    <div class="mb-12">
+                                        <a href="/blog" class="text-blue-600 hover:text-blue-800 transition inline-flex text-left gap-2 mb-6">
+                                            <span>"← Back to Blog"</span>
+                                        </a>
+                                        <p class="text-sm font-semibold uppercase tracking-wide text-blue-600 mb-2">
+                                            "Series"
+                                        </p>
+                                        <h1 class="text-5xl font-bold text-gray-900 mb-4">
+                                            {listing.name.clone()}
+                                        </h1>
+                                        <p class="text-xl text-gray-600">
+                                            {format!("{} parts", listing.posts.len())}
+                                        </p>
+                                    </div>
+                                    <ol class="space-y-8">
+                                        {listing.posts.into_iter().enumerate().map(|(i, post)| {
+                                            view! {
+                                                <li>
+                                                    <p class="text-sm text-gray-500 mb-2">
+                                                        {format!("Part {}", i + 1)}
+                                                    </p>
+                                                    <BlogPostCard post=post />
+                                                </li>
+                                            }
+                                        }).collect::<Vec<_>>()}
+                                    </ol>
+                                }.into_any(),
+                                Some(Ok(None)) => view! {
+                                    <div class="text-center py-12">
+                                        <h1 class="text-3xl font-bold text-gray-900 mb-4">
+                                            "Series Not Found"
+                                        </h1>
+                                        <a href="/blog" class="text-blue-600 hover:text-blue-800 transition">
+                                            "← Back to Blog"
+                                        </a>
+                                    </div>
+                                }.into_any(),
+                                Some(Err(e)) => view! {
+                                    <BlogPostError error=format!("Error loading series: {}", e) />
+                                }.into_any(),
+                                None => view! { <BlogPostLoading /> }.into_any(),
+                            }
+                        }}
+                    </Suspense>
+                </div>
+            </div>
+        </Layout>
+    }
+}
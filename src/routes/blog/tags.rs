@@ -0,0 +1,134 @@
+use crate::components::blog::{BlogPostCard, BlogPostError, BlogPostLoading};
+use crate::components::Layout;
+use crate::server_functions::tags::{fetch_posts_by_tag, fetch_tags};
+use leptos::prelude::*;
+use leptos::{component, IntoView};
+use leptos_meta::Title;
+use leptos_router::hooks::use_params_map;
+
+/// All tags with their post counts
+#[component]
+pub fn TagIndexPage() -> impl IntoView {
+    let tags = Resource::new(|| (), |_| fetch_tags());
+
+    view! {
+        <Title text="Tags" />
+        <Layout>
+            <div class="pt-24 pb-20 px-4 sm:px-6 lg:px-8">
+                <div class="max-w-5xl mx-auto">
+                    <div class="mb-12">
+                        <a href="/blog" class="text-blue-600 hover:text-blue-800 transition inline-flex text-left gap-2 mb-6">
+                            <span>"← Back to Blog"</span>
+                        </a>
+                        <h1 class="text-5xl font-bold text-gray-900 mb-4">
+                            "Tags"
+                        </h1>
+                        <p class="text-xl text-gray-600">
+                            "Browse posts by topic."
+                        </p>
+                    </div>
+
+                    <Suspense fallback=move || view! { <BlogPostLoading /> }>
+                        {move || {
+                            match tags.get() {
+                                Some(Ok(tags)) if tags.is_empty() => view! {
+                                    <div class="py-12">
+                                        <p class="text-gray-500 text-lg">
+                                            "No tags yet."
+                                        </p>
+                                    </div>
+                                }.into_any(),
+                                Some(Ok(tags)) => view! {
+                                    <ul class="flex flex-wrap gap-3">
+                                        {tags.into_iter().map(|tag| {
+                                            view! {
+                                                <li>
+                                                    <a
+                                                        href=format!("/blog/tag/{}", tag.slug)
+                                                        class="inline-flex items-center gap-2 px-4 py-2 bg-blue-100 text-blue-700 rounded-full hover:bg-blue-200 transition"
+                                                    >
+                                                        <span>{tag.name}</span>
+                                                        <span class="text-sm text-blue-500">{tag.count}</span>
+                                                    </a>
+                                                </li>
+                                            }
+                                        }).collect::<Vec<_>>()}
+                                    </ul>
+                                }.into_any(),
+                                Some(Err(e)) => view! {
+                                    <BlogPostError error=format!("Error loading tags: {}", e) />
+                                }.into_any(),
+                                None => view! { <BlogPostLoading /> }.into_any(),
+                            }
+                        }}
+                    </Suspense>
+                </div>
+            </div>
+        </Layout>
+    }
+}
+
+/// Posts with a single tag
+#[component]
+pub fn TagPage() -> impl IntoView {
+    let params = use_params_map();
+    let tag = move || params.read().get("tag").unwrap_or_default();
+
+    let listing = Resource::new(tag, fetch_posts_by_tag);
+
+    view! {
+        <Layout>
+            <div class="pt-24 pb-20 px-4 sm:px-6 lg:px-8">
+                <div class="max-w-5xl mx-auto">
+                    <Suspense fallback=move || view! { <BlogPostLoading /> }>
+                        {move || {
+                            match listing.get() {
+                                Some(Ok(Some(listing))) => view! {
+                                    <Title text=format!("Posts tagged {}", listing.tag.name) />
+                                    <div class="mb-12">
+                                        <a href="/blog/tags" class="text-blue-600 hover:text-blue-800 transition inline-flex text-left gap-2 mb-6">
+                                            <span>"← All Tags"</span>
+                                        </a>
+                                        <h1 class="text-5xl font-bold text-gray-900 mb-4">
+                                            {format!("#{}", listing.tag.name)}
+                                        </h1>
+                                        <p class="text-xl text-gray-600">
+                                            {format!("{} posts", listing.tag.count)}
+                                            " · "
+                                            <a
+                                                href=format!("/blog/tag/{}/feed.xml", listing.tag.slug)
+                                                rel="external"
+                                                class="text-blue-600 hover:text-blue-800 transition"
+                                            >
+                                                "RSS"
+                                            </a>
+                                        </p>
+                                    </div>
+                                    <div class="space-y-8">
+                                        {listing.posts.into_iter().map(|post| {
+                                            view! { <BlogPostCard post=post /> }
+                                        }).collect::<Vec<_>>()}
+                                    </div>
+                                }.into_any(),
+                                Some(Ok(None)) => view! {
+                                    <div class="text-center py-12">
+                                        <h1 class="text-3xl font-bold text-gray-900 mb-4">
+                                            "Tag Not Found"
+                                        </h1>
+                                        <a href="/blog/tags" class="text-blue-600 hover:text-blue-800 transition">
+                                            "← All Tags"
+                                        </a>
+                                    </div>
+                                }.into_any(),
+                                Some(Err(e)) => view! {
+                                    <BlogPostError error=format!("Error loading posts: {}", e) />
+                                }.into_any(),
+                                None => view! { <BlogPostLoading /> }.into_any(),
+                            }
+                        }}
+                    </Suspense>
+                </div>
+            </div>
+        </Layout>
+    }
+}
@@ -1,10 +1,15 @@
+use crate::components::structured_data::{website, JsonLd};
 use crate::components::Layout;
+use crate::config::use_site_config;
 use leptos::prelude::*;
 use leptos::{component, IntoView};
 
 #[component]
 pub fn HomePage() -> impl IntoView {
+    let config = use_site_config();
+
     view! {
+        <JsonLd data=website(&config) />
         <Layout>
             <div class="pt-32 pb-20 px-4 sm:px-6 lg:px-8">
                 <div class="max-w-7xl mx-auto">
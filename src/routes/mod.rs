@@ -1,2 +1,5 @@
+pub mod admin;
 pub mod blog;
 pub mod home_page;
+pub mod page;
+pub mod search;
@@ -0,0 +1,52 @@
+use crate::app::NotFound;
+use crate::components::blog::{BlogPostContent, BlogPostError, BlogPostLoading};
+use crate::components::Layout;
+use crate::server_functions::pages::fetch_page;
+use leptos::prelude::*;
+use leptos::{component, IntoView};
+use leptos_meta::{Meta, Title};
+use leptos_router::hooks::use_params_map;
+
+/// Standalone markdown page at any path no other route matched.
+/// Paths without a page render [`NotFound`].
+#[component]
+pub fn PageRoute() -> impl IntoView {
+    let params = use_params_map();
+    let path = move || params.read().get("any").unwrap_or_default();
+
+    let page = Resource::new(path, fetch_page);
+
+    view! {
+        <Suspense fallback=move || view! { <BlogPostLoading /> }>
+            {move || {
+                match page.get() {
+                    Some(Ok(Some(page))) => view! {
+                        <Title text=page.meta.title.clone() />
+                        {(!page.meta.description.is_empty()).then(|| view! {
+                            <Meta name="description" content=page.meta.description.clone() />
+                        })}
+                        <Layout>
+                            <div class="pt-24 pb-20 px-4 sm:px-6 lg:px-8">
+                                <article class="max-w-3xl mx-auto">
+                                    <h1 class="text-4xl md:text-5xl font-bold text-gray-900 mb-8">
+                                        {page.meta.title}
+                                    </h1>
+                                    <BlogPostContent html_content=page.content />
+                                </article>
+                            </div>
+                        </Layout>
+                    }.into_any(),
+                    Some(Ok(None)) => view! { <NotFound /> }.into_any(),
+                    Some(Err(e)) => view! {
+                        <Layout>
+                            <div class="pt-24 pb-20 px-4 sm:px-6 lg:px-8">
+                                <BlogPostError error=format!("Error loading page: {}", e) />
+                            </div>
+                        </Layout>
+                    }.into_any(),
+                    None => view! { <BlogPostLoading /> }.into_any(),
+                }
+            }}
+        </Suspense>
+    }
+}
@@ -0,0 +1,190 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::components::blog::{BlogPostError, BlogPostLoading, TagLink};
+use crate::components::Layout;
+use crate::models::search::SearchHit;
+use crate::search::StaticSearchIndex;
+use crate::server_functions::search::{fetch_search_index, search_posts, DEFAULT_SEARCH_LIMIT};
+use crate::server_functions::static_export::is_static_export;
+use leptos::prelude::*;
+use leptos::{component, IntoView};
+use leptos_meta::{Meta, Title};
+use leptos_router::components::Form;
+use leptos_router::hooks::{use_navigate, use_query_map};
+use leptos_router::location::Url;
+use leptos_router::NavigateOptions;
+
+/// How long typing has to pause before the results update
+const SEARCH_DEBOUNCE: Duration = Duration::from_millis(250);
+
+/// Full-text search over the blog, served at `/search?q=...`.
+///
+/// The query string is the only state, so the page works as a plain GET
+/// form before (or without) hydration. Once hydrated, typing updates the
+/// URL in place after a short pause and the results follow.
+///
+/// Static exports have no server to search, so there the page loads the
+/// exported index once and searches it in the browser.
+#[component]
+pub fn SearchPage() -> impl IntoView {
+    let query_map = use_query_map();
+    let query = move || query_map.read().get("q").unwrap_or_default();
+
+    let index = StoredValue::new(None::<Arc<StaticSearchIndex>>);
+    let results = Resource::new(query, move |query| async move {
+        if query.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+        if cfg!(feature = "hydrate") && is_static_export() {
+            let loaded = match index.get_value() {
+                Some(loaded) => loaded,
+                None => {
+                    let loaded = Arc::new(fetch_search_index().await?);
+                    index.set_value(Some(loaded.clone()));
+                    loaded
+                }
+            };
+            return Ok(loaded.search(&query, DEFAULT_SEARCH_LIMIT));
+        }
+        search_posts(query, None).await
+    });
+
+    let navigate = use_navigate();
+    let pending = StoredValue::new(None::<TimeoutHandle>);
+    let on_input = move |ev| {
+        let value = event_target_value(&ev);
+        if let Some(handle) = pending.get_value() {
+            handle.clear();
+        }
+
+        let navigate = navigate.clone();
+        let handle = set_timeout_with_handle(
+            move || {
+                let href = match value.trim() {
+                    "" => "/search".to_string(),
+                    q => format!("/search?q={}", Url::escape(q)),
+                };
+                navigate(
+                    &href,
+                    NavigateOptions {
+                        replace: true,
+                        scroll: false,
+                        ..Default::default()
+                    },
+                );
+            },
+            SEARCH_DEBOUNCE,
+        )
+        .ok();
+        pending.set_value(handle);
+    };
+
+    view! {
+        <Title text="Search" />
+        <Meta name="robots" content="noindex" />
+        <Layout>
+            <div class="pt-24 pb-20 px-4 sm:px-6 lg:px-8">
+                <div class="max-w-5xl mx-auto">
+                    <div class="mb-12">
+                        <h1 class="text-5xl font-bold text-gray-900 mb-6">
+                            "Search"
+                        </h1>
+                        <Form method="GET" action="/search" noscroll=true>
+                            <div class="flex gap-3">
+                                <input
+                                    type="search"
+                                    name="q"
+                                    // The attribute fills in the box on the server,
+                                    // the property keeps it in sync on navigation
+                                    value=query
+                                    prop:value=query
+                                    on:input=on_input
+                                    placeholder="Search posts…"
+                                    aria-label="Search posts"
+                                    autocomplete="off"
+                                    class="flex-1 px-4 py-3 text-lg border border-gray-300 rounded-lg focus:outline-none focus:ring-2 focus:ring-blue-500"
+                                />
+                                <button
+                                    type="submit"
+                                    class="px-6 py-3 bg-blue-600 text-white font-semibold rounded-lg hover:bg-blue-700 transition"
+                                >
+                                    "Search"
+                                </button>
+                            </div>
+                        </Form>
+                    </div>
+
+                    <Suspense fallback=move || view! { <BlogPostLoading /> }>
+                        {move || {
+                            match results.get() {
+                                Some(Ok(_)) if query().trim().is_empty() => ().into_any(),
+                                Some(Ok(hits)) if hits.is_empty() => view! {
+                                    <div class="py-12">
+                                        <p class="text-gray-500 text-lg">
+                                            {format!("No posts match \u{201c}{}\u{201d}.", query())}
+                                        </p>
+                                    </div>
+                                }.into_any(),
+                                Some(Ok(hits)) => view! {
+                                    <p class="text-gray-500 mb-6">
+                                        {match hits.len() {
+                                            1 => "1 result".to_string(),
+                                            n => format!("{} results", n),
+                                        }}
+                                    </p>
+                                    <div class="space-y-8">
+                                        {hits.into_iter().map(|hit| {
+                                            view! { <SearchResult hit=hit /> }
+                                        }).collect::<Vec<_>>()}
+                                    </div>
+                                }.into_any(),
+                                Some(Err(e)) => view! {
+                                    <BlogPostError error=format!("Error searching posts: {}", e) />
+                                }.into_any(),
+                                None => view! { <BlogPostLoading /> }.into_any(),
+                            }
+                        }}
+                    </Suspense>
+                </div>
+            </div>
+        </Layout>
+    }
+}
+
+/// A matching post with the snippet that matched
+#[component]
+fn SearchResult(hit: SearchHit) -> impl IntoView {
+    let meta = hit.meta;
+    let href = format!("/blog/{}", meta.slug);
+
+    view! {
+        <article class="bg-white border border-gray-200 rounded-2xl overflow-hidden shadow-all text-left">
+            <div class="p-6">
+                <a href=href class="group">
+                    <h2 class="text-2xl font-bold text-gray-900 group-hover:text-blue-600 transition mb-3">
+                        {meta.title}
+                    </h2>
+
+                    <p class="search-snippet text-gray-600 mb-4" inner_html=hit.snippet></p>
+
+                    <p class="text-sm text-gray-500 mb-4">
+                        {meta.date.to_string()}
+                        " · "
+                        {format!("{} min read", meta.reading_time)}
+                    </p>
+                </a>
+                <div class="flex flex-wrap gap-2">
+                    {meta.tags.into_iter().map(|tag| {
+                        view! {
+                            <TagLink
+                                tag=tag
+                                class="px-3 py-1 bg-gray-100 text-gray-700 text-sm rounded-full hover:bg-gray-200 transition"
+                            />
+                        }
+                    }).collect::<Vec<_>>()}
+                </div>
+            </div>
+        </article>
+    }
+}
@@ -0,0 +1,522 @@
+//! In-memory inverted index over the posts in the `ContentStore`.
+//!
+//! Text is lowercased, split on anything that isn't alphanumeric and reduced
+//! to English stems, so "running" finds "runs". Matches in the title count
+//! more than matches in tags, the description or the body.
+//!
+//! The index is built on the server, but static exports ship a
+//! [`StaticSearchIndex`] so the browser can search without one.
+
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use rust_stemmers::{Algorithm, Stemmer};
+use serde::{Deserialize, Serialize};
+
+use crate::models::blog::{BlogPost, BlogPostMeta};
+use crate::models::search::SearchHit;
+
+static STEMMER: LazyLock<Stemmer> = LazyLock::new(|| Stemmer::create(Algorithm::English));
+
+const TITLE_WEIGHT: f32 = 5.0;
+const TAG_WEIGHT: f32 = 4.0;
+const DESCRIPTION_WEIGHT: f32 = 2.0;
+const BODY_WEIGHT: f32 = 1.0;
+
+/// Characters of context shown on each side of the first match
+const SNIPPET_CONTEXT: usize = 80;
+
+/// Splits text into lowercase words
+fn words(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+}
+
+fn stem(word: &str) -> String {
+    STEMMER.stem(word).into_owned()
+}
+
+/// Strips tags from rendered HTML and decodes the entities pulldown-cmark
+/// emits. The `#` links added next to headings are dropped entirely.
+pub fn html_to_text(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        text.push_str(&rest[..start]);
+        text.push(' ');
+        rest = &rest[start..];
+
+        let end = if rest.starts_with(r#"<a class="heading-anchor""#) {
+            rest.find("</a>").map(|i| i + "</a>".len())
+        } else {
+            rest.find('>').map(|i| i + 1)
+        };
+        rest = &rest[end.unwrap_or(rest.len())..];
+    }
+    text.push_str(rest);
+
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Escapes text for HTML, like `content::xml_escape` which is server-only
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Weighted term frequencies of one post, plus its text for snippets
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Document {
+    terms: HashMap<String, f32>,
+    body: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SearchIndex {
+    documents: HashMap<String, Document>,
+    /// Term -> slugs of the posts containing it
+    postings: HashMap<String, Vec<String>>,
+}
+
+impl SearchIndex {
+    /// Indexes `post`, replacing any earlier version with the same slug
+    pub fn add(&mut self, post: &BlogPost) {
+        let slug = post.meta.slug.clone();
+        self.remove(&slug);
+
+        let body = html_to_text(&post.content);
+        let mut terms: HashMap<String, f32> = HashMap::new();
+        let fields = [
+            (post.meta.title.as_str(), TITLE_WEIGHT),
+            (post.meta.description.as_str(), DESCRIPTION_WEIGHT),
+            (body.as_str(), BODY_WEIGHT),
+        ];
+        for (text, weight) in fields {
+            for word in words(text) {
+                *terms.entry(stem(&word)).or_default() += weight;
+            }
+        }
+        for tag in &post.meta.tags {
+            for word in words(tag) {
+                *terms.entry(stem(&word)).or_default() += TAG_WEIGHT;
+            }
+        }
+
+        for term in terms.keys() {
+            self.postings
+                .entry(term.clone())
+                .or_default()
+                .push(slug.clone());
+        }
+        self.documents.insert(slug, Document { terms, body });
+    }
+
+    /// Drops the post with `slug` from the index
+    pub fn remove(&mut self, slug: &str) {
+        let Some(document) = self.documents.remove(slug) else {
+            return;
+        };
+        for term in document.terms.keys() {
+            if let Some(slugs) = self.postings.get_mut(term) {
+                slugs.retain(|s| s != slug);
+                if slugs.is_empty() {
+                    self.postings.remove(term);
+                }
+            }
+        }
+    }
+
+    /// Terms of the index a query word matches: its exact stem, or while
+    /// the user is still typing the last word, every term it's a prefix of
+    fn expand(&self, word: &str, is_last: bool) -> Vec<&str> {
+        let stemmed = stem(word);
+        let mut terms: Vec<&str> = self
+            .postings
+            .get_key_value(&stemmed)
+            .map(|(term, _)| vec![term.as_str()])
+            .unwrap_or_default();
+
+        if is_last && word.chars().count() >= 2 {
+            terms.extend(
+                self.postings
+                    .keys()
+                    .filter(|term| term.starts_with(word) && **term != stemmed)
+                    .map(String::as_str),
+            );
+        }
+        terms
+    }
+
+    /// Slugs of the posts matching every word of `query`, best match first
+    pub fn search(&self, query: &str) -> Vec<(String, f32)> {
+        let query: Vec<String> = words(query).collect();
+        if query.is_empty() {
+            return Vec::new();
+        }
+
+        let total = self.documents.len() as f32;
+        let mut scores: HashMap<&str, f32> = HashMap::new();
+
+        for (i, word) in query.iter().enumerate() {
+            let mut word_scores: HashMap<&str, f32> = HashMap::new();
+            for term in self.expand(word, i == query.len() - 1) {
+                let slugs = &self.postings[term];
+                let idf = (1.0 + total / slugs.len() as f32).ln();
+                for slug in slugs {
+                    let weight = self.documents[slug].terms[term];
+                    let score = word_scores.entry(slug.as_str()).or_default();
+                    *score = score.max((1.0 + weight).ln() * idf);
+                }
+            }
+
+            if i == 0 {
+                scores = word_scores;
+            } else {
+                scores.retain(|slug, _| word_scores.contains_key(slug));
+                for (slug, score) in scores.iter_mut() {
+                    *score += word_scores[slug];
+                }
+            }
+        }
+
+        let mut ranked: Vec<(String, f32)> = scores
+            .into_iter()
+            .map(|(slug, score)| (slug.to_string(), score))
+            .collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        ranked
+    }
+
+    /// The best `limit` posts matching `query`, with snippets. `meta` looks
+    /// up a post by slug and returns `None` for posts that shouldn't be found.
+    pub fn hits(
+        &self,
+        query: &str,
+        limit: usize,
+        meta: impl Fn(&str) -> Option<BlogPostMeta>,
+    ) -> Vec<SearchHit> {
+        self.search(query)
+            .into_iter()
+            .filter_map(|(slug, score)| {
+                Some(SearchHit {
+                    meta: meta(&slug)?,
+                    snippet: self.snippet(&slug, query),
+                    score,
+                })
+            })
+            .take(limit)
+            .collect()
+    }
+
+    /// Posts whose text is most like the post with `slug`, by cosine
+    /// similarity of their tf-idf weighted terms (0 to 1), best match first
+    pub fn similar(&self, slug: &str) -> Vec<(String, f32)> {
+        let Some(document) = self.documents.get(slug) else {
+            return Vec::new();
+        };
+
+        let total = self.documents.len() as f32;
+        let tf_idf = |term: &str, weight: f32| {
+            let idf = (1.0 + total / self.postings[term].len() as f32).ln();
+            (1.0 + weight).ln() * idf
+        };
+        let norm = |doc: &Document| {
+            doc.terms
+                .iter()
+                .map(|(term, weight)| tf_idf(term, *weight).powi(2))
+                .sum::<f32>()
+                .sqrt()
+        };
+
+        let own_norm = norm(document);
+        let mut dots: HashMap<&str, f32> = HashMap::new();
+        for (term, weight) in &document.terms {
+            let own = tf_idf(term, *weight);
+            for other in &self.postings[term] {
+                if other != slug {
+                    let theirs = tf_idf(term, self.documents[other].terms[term]);
+                    *dots.entry(other.as_str()).or_default() += own * theirs;
+                }
+            }
+        }
+
+        let mut ranked: Vec<(String, f32)> = dots
+            .into_iter()
+            .map(|(other, dot)| {
+                let norms = own_norm * norm(&self.documents[other]);
+                let similarity = if norms > 0.0 { dot / norms } else { 0.0 };
+                (other.to_string(), similarity)
+            })
+            .collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        ranked
+    }
+
+    /// Escaped excerpt of the post's body around the first word matching
+    /// `query`, with every matching word wrapped in `<mark>`
+    pub fn snippet(&self, slug: &str, query: &str) -> String {
+        let Some(document) = self.documents.get(slug) else {
+            return String::new();
+        };
+        let query: Vec<String> = words(query).collect();
+        let matches = |word: &str| {
+            let word = word.to_lowercase();
+            let stemmed = stem(&word);
+            query
+                .iter()
+                .any(|q| stem(q) == stemmed || word.starts_with(q.as_str()))
+        };
+
+        let body = &document.body;
+        let mut spans = Vec::new();
+        let mut start = None;
+        for (i, c) in body
+            .char_indices()
+            .chain(std::iter::once((body.len(), ' ')))
+        {
+            match (c.is_alphanumeric(), start) {
+                (true, None) => start = Some(i),
+                (false, Some(s)) => {
+                    if matches(&body[s..i]) {
+                        spans.push(s..i);
+                    }
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+
+        let Some(first) = spans.first() else {
+            return html_escape(&truncate(body, 2 * SNIPPET_CONTEXT));
+        };
+
+        // Widen to the context size, then shrink back to whole words
+        let mut from = floor_char_boundary(body, first.start.saturating_sub(SNIPPET_CONTEXT));
+        if from > 0 {
+            from = body[from..first.start]
+                .find(' ')
+                .map_or(first.start, |i| from + i + 1);
+        }
+        let mut to = floor_char_boundary(body, (first.end + SNIPPET_CONTEXT).min(body.len()));
+        if to < body.len() {
+            to = body[first.end..to]
+                .rfind(' ')
+                .map_or(first.end, |i| first.end + i);
+        }
+
+        let mut snippet = String::new();
+        if from > 0 {
+            snippet.push('…');
+        }
+        let mut pos = from;
+        for span in spans.iter().filter(|s| s.start >= from && s.end <= to) {
+            snippet.push_str(&html_escape(&body[pos..span.start]));
+            snippet.push_str("<mark>");
+            snippet.push_str(&html_escape(&body[span.clone()]));
+            snippet.push_str("</mark>");
+            pos = span.end;
+        }
+        snippet.push_str(&html_escape(&body[pos..to]));
+        if to < body.len() {
+            snippet.push('…');
+        }
+        snippet
+    }
+}
+
+fn floor_char_boundary(text: &str, mut index: usize) -> usize {
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+/// First `len` characters of `text`, cut back to a whole word
+fn truncate(text: &str, len: usize) -> String {
+    match text.char_indices().nth(len) {
+        None => text.to_string(),
+        Some((end, _)) => {
+            let end = text[..end].rfind(' ').unwrap_or(end);
+            format!("{}…", &text[..end])
+        }
+    }
+}
+
+/// Index of the listed posts together with their metadata. Static exports
+/// ship it as JSON and search it in the browser.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StaticSearchIndex {
+    index: SearchIndex,
+    posts: HashMap<String, BlogPostMeta>,
+}
+
+impl StaticSearchIndex {
+    pub fn new<'a>(posts: impl IntoIterator<Item = &'a BlogPost>) -> Self {
+        let mut search = Self::default();
+        for post in posts {
+            search.index.add(post);
+            search
+                .posts
+                .insert(post.meta.slug.clone(), post.meta.clone());
+        }
+        search
+    }
+
+    /// The best `limit` posts matching `query`, like `ContentStore::search`
+    pub fn search(&self, query: &str, limit: usize) -> Vec<SearchHit> {
+        self.index
+            .hits(query, limit, |slug| self.posts.get(slug).cloned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn post(slug: &str, title: &str, tags: &[&str], content: &str) -> BlogPost {
+        serde_json::from_value(serde_json::json!({
+            "slug": slug,
+            "title": title,
+            "date": "2024-01-01",
+            "tags": tags,
+            "content": content,
+        }))
+        .unwrap()
+    }
+
+    fn index(posts: &[BlogPost]) -> SearchIndex {
+        let mut index = SearchIndex::default();
+        for post in posts {
+            index.add(post);
+        }
+        index
+    }
+
+    fn slugs(results: Vec<(String, f32)>) -> Vec<String> {
+        results.into_iter().map(|(slug, _)| slug).collect()
+    }
+
+    #[test]
+    fn matches_stems() {
+        let index = index(&[post("a", "Running servers", &[], "<p>It runs.</p>")]);
+        assert_eq!(slugs(index.search("run")), ["a"]);
+        assert_eq!(slugs(index.search("RUNS")), ["a"]);
+        assert_eq!(slugs(index.search("server")), ["a"]);
+    }
+
+    #[test]
+    fn expands_only_the_last_word_as_prefix() {
+        let index = index(&[post("a", "Rust tips", &[], "<p>Fearless code</p>")]);
+        assert_eq!(slugs(index.search("rus")), ["a"]);
+        assert_eq!(slugs(index.search("fearless ru")), ["a"]);
+        assert!(index.search("rus tips").is_empty());
+        // A single letter is too short to expand
+        assert!(index.search("r").is_empty());
+    }
+
+    #[test]
+    fn requires_every_word() {
+        let index = index(&[
+            post("both", "Rust and Python", &[], ""),
+            post("rust", "Rust only", &[], ""),
+        ]);
+        assert_eq!(slugs(index.search("rust python")), ["both"]);
+        assert!(index.search("rust haskell").is_empty());
+        assert!(index.search(" ,. ").is_empty());
+    }
+
+    #[test]
+    fn ranks_title_and_tags_above_body() {
+        let index = index(&[
+            post("body", "Notes", &[], "<p>About leptos</p>"),
+            post("tag", "Notes", &["Leptos"], ""),
+            post("title", "Leptos", &[], ""),
+        ]);
+        assert_eq!(slugs(index.search("leptos")), ["title", "tag", "body"]);
+    }
+
+    #[test]
+    fn forgets_removed_and_replaced_posts() {
+        let mut index = index(&[post("a", "Rust", &[], "")]);
+        index.add(&post("a", "Python", &[], ""));
+        assert!(index.search("rust").is_empty());
+        assert_eq!(slugs(index.search("python")), ["a"]);
+
+        index.remove("a");
+        assert!(index.search("python").is_empty());
+        assert!(index.postings.is_empty());
+    }
+
+    #[test]
+    fn strips_html_and_heading_anchors() {
+        let html = r##"<h2 id="x">Title <a class="heading-anchor" href="#x">#</a></h2><p>Tom &amp; &lt;Jerry&gt;</p>"##;
+        assert_eq!(html_to_text(html), "Title Tom & <Jerry>");
+    }
+
+    #[test]
+    fn marks_matches_and_escapes_snippets() {
+        let index = index(&[post(
+            "a",
+            "Post",
+            &[],
+            "<p>Use &lt;script&gt; tags with care when running code.</p>",
+        )]);
+        assert_eq!(
+            index.snippet("a", "run script"),
+            "Use &lt;<mark>script</mark>&gt; tags with care when <mark>running</mark> code."
+        );
+        assert_eq!(index.snippet("missing", "run"), "");
+    }
+
+    #[test]
+    fn cuts_snippets_of_multibyte_text_on_char_boundaries() {
+        let filler = "héllo wörld ✨ ".repeat(20);
+        let content = format!("<p>{}match {}</p>", filler, filler);
+        let index = index(&[post("a", "Post", &[], &content)]);
+
+        let snippet = index.snippet("a", "match");
+        assert!(
+            snippet.starts_with('…') && snippet.ends_with('…'),
+            "{}",
+            snippet
+        );
+        assert!(snippet.contains("<mark>match</mark>"));
+
+        // Without a match the start of the body is shown
+        let snippet = index.snippet("a", "nothing");
+        assert!(
+            snippet.starts_with("héllo") && snippet.ends_with('…'),
+            "{}",
+            snippet
+        );
+    }
+
+    #[test]
+    fn truncates_to_whole_words() {
+        assert_eq!(truncate("ä b", 10), "ä b");
+        assert_eq!(truncate("äää bbb ccc", 6), "äää…");
+        assert_eq!(truncate("ääääää", 3), "äää…");
+        assert_eq!(floor_char_boundary("ä", 1), 0);
+    }
+
+    #[test]
+    fn searches_static_index_with_metadata() {
+        let posts = [post("a", "Rust", &[], ""), post("b", "Python", &[], "")];
+        let index = StaticSearchIndex::new(&posts);
+        let hits = index.search("rust", 10);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].meta.title, "Rust");
+        assert!(index.search("rust", 0).is_empty());
+    }
+}
@@ -0,0 +1,329 @@
+//! Server functions behind the `/admin` area. Everything except logging in
+//! needs a valid session cookie, see [`crate::content::auth`].
+
+use leptos::server_fn::codec::{GetUrl, Json};
+use leptos::{prelude::ServerFnError, *};
+
+#[allow(unused_imports)]
+use crate::models::admin::{AdminEntry, PostStatus};
+#[allow(unused_imports)]
+use crate::models::blog::BlogPost;
+
+/// Message of the error returned when the session is missing or expired
+pub const UNAUTHORIZED: &str = "Please log in";
+
+/// The admin config and request of the current server function call
+#[cfg(feature = "ssr")]
+async fn admin_request() -> Result<
+    (
+        actix_web::web::Data<crate::config::SiteConfig>,
+        actix_web::HttpRequest,
+    ),
+    ServerFnError,
+> {
+    let config = leptos_actix::extract::<actix_web::web::Data<crate::config::SiteConfig>>().await?;
+    if config.admin.password_hash.is_none() {
+        return Err(ServerFnError::new(
+            "The admin area is disabled. Set `[admin] password_hash` in site.toml to enable it.",
+        ));
+    }
+    let request = leptos_actix::extract::<actix_web::HttpRequest>().await?;
+    Ok((config, request))
+}
+
+/// Whether the request carries a valid session cookie
+#[cfg(feature = "ssr")]
+async fn is_logged_in() -> Result<bool, ServerFnError> {
+    use crate::content::auth::{verify_session, SESSION_COOKIE};
+
+    let (config, request) = admin_request().await?;
+    Ok(request.cookie(SESSION_COOKIE).is_some_and(|cookie| {
+        verify_session(&config.admin.username, cookie.value(), chrono::Utc::now())
+    }))
+}
+
+/// Fails unless the request carries a valid session cookie
+#[cfg(feature = "ssr")]
+async fn require_admin() -> Result<(), ServerFnError> {
+    match is_logged_in().await? {
+        true => Ok(()),
+        false => Err(ServerFnError::new(UNAUTHORIZED)),
+    }
+}
+
+/// Sets the session cookie, or removes it when `token` is `None`
+#[cfg(feature = "ssr")]
+fn set_session_cookie(token: Option<String>, secure: bool) -> Result<(), ServerFnError> {
+    use actix_web::cookie::{time::Duration, Cookie, SameSite};
+    use actix_web::http::header::{HeaderValue, SET_COOKIE};
+
+    use crate::content::auth::{SESSION_COOKIE, SESSION_HOURS};
+
+    let max_age = match token {
+        Some(_) => Duration::hours(SESSION_HOURS),
+        None => Duration::ZERO,
+    };
+    let cookie = Cookie::build(SESSION_COOKIE, token.unwrap_or_default())
+        .path("/")
+        .http_only(true)
+        .secure(secure)
+        .same_site(SameSite::Strict)
+        .max_age(max_age)
+        .finish();
+
+    let response = leptos::prelude::expect_context::<leptos_actix::ResponseOptions>();
+    response.insert_header(SET_COOKIE, HeaderValue::from_str(&cookie.to_string())?);
+    Ok(())
+}
+
+/// Whether the current visitor is logged in to the admin area. Fails if the
+/// admin area is disabled.
+#[server(name = AdminSession, prefix = "/api", input = GetUrl, output = Json)]
+pub async fn admin_session() -> Result<bool, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        is_logged_in().await
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new(
+            "This function is only available on the server",
+        ))
+    }
+}
+
+/// Checks the credentials against `[admin]` and starts a session. Clients
+/// with too many failed attempts are turned away without checking.
+#[server(name = AdminLogin, prefix = "/api")]
+pub async fn admin_login(username: String, password: String) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::content::auth::{
+            check_credentials, login_throttled, record_failed_login, sign_session,
+        };
+        use crate::content::rate_limit::client_id;
+
+        let (config, request) = admin_request().await?;
+        let client = client_id(&request, &config.comments.trusted_proxies);
+        let now = chrono::Utc::now();
+        if login_throttled(&client, now) {
+            return Err(ServerFnError::new(
+                "Too many failed logins, please try again later",
+            ));
+        }
+
+        // Hashing is slow on purpose, keep it off the async workers
+        let admin = config.admin.clone();
+        let valid =
+            actix_web::web::block(move || check_credentials(&admin, &username, &password)).await?;
+        if !valid {
+            record_failed_login(&client, now);
+            return Err(ServerFnError::new("Wrong username or password"));
+        }
+
+        let token = sign_session(&config.admin.username, now);
+        set_session_cookie(Some(token), config.base_url.starts_with("https://"))
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    {
+        let _ = (username, password);
+        Err(ServerFnError::new(
+            "This function is only available on the server",
+        ))
+    }
+}
+
+/// Ends the session
+#[server(name = AdminLogout, prefix = "/api")]
+pub async fn admin_logout() -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let (config, _) = admin_request().await?;
+        set_session_cookie(None, config.base_url.starts_with("https://"))
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new(
+            "This function is only available on the server",
+        ))
+    }
+}
+
+/// Every entry of the content source, including drafts, scheduled posts and
+/// entries that failed to parse
+#[server(name = FetchAdminEntries, prefix = "/api", input = GetUrl, output = Json)]
+pub async fn fetch_admin_entries() -> Result<Vec<AdminEntry>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        require_admin().await?;
+        let store = crate::content::use_content_store().await?;
+        let now = chrono::Utc::now();
+        let errors = store.errors();
+
+        let mut entries: Vec<AdminEntry> = store
+            .entries()
+            .into_iter()
+            .map(|(id, slug)| {
+                let meta = slug.and_then(|slug| store.get(&slug)).map(|post| post.meta);
+                let status = meta.as_ref().map(|meta| {
+                    if meta.draft {
+                        PostStatus::Draft
+                    } else if !meta.is_published(now) {
+                        PostStatus::Scheduled
+                    } else if meta.unlisted {
+                        PostStatus::Unlisted
+                    } else {
+                        PostStatus::Published
+                    }
+                });
+                let error = errors
+                    .iter()
+                    .find(|diagnostic| diagnostic.entry == id)
+                    .map(|diagnostic| diagnostic.message.clone());
+                AdminEntry {
+                    id,
+                    meta,
+                    status,
+                    error,
+                }
+            })
+            .collect();
+        // Broken entries first, then the newest posts
+        entries.sort_by(|a, b| {
+            b.error
+                .is_some()
+                .cmp(&a.error.is_some())
+                .then_with(|| {
+                    let published =
+                        |entry: &AdminEntry| entry.meta.as_ref().map(|meta| meta.published_at());
+                    published(b).cmp(&published(a))
+                })
+                .then_with(|| a.id.cmp(&b.id))
+        });
+        Ok(entries)
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new(
+            "This function is only available on the server",
+        ))
+    }
+}
+
+/// Raw markdown of the entry `id`
+#[server(name = FetchAdminEntry, prefix = "/api", input = GetUrl, output = Json)]
+pub async fn fetch_admin_entry(id: String) -> Result<String, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        require_admin().await?;
+        let store = crate::content::use_content_store().await?;
+        store.source().read(&id).map_err(ServerFnError::new)
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    {
+        let _ = id;
+        Err(ServerFnError::new(
+            "This function is only available on the server",
+        ))
+    }
+}
+
+/// Renders `content` like a saved post would be, for the editor preview.
+/// Images keep their original `<img>`; variants are only generated once the
+/// post is saved.
+#[server(name = PreviewAdminEntry, prefix = "/api")]
+pub async fn preview_admin_entry(content: String) -> Result<BlogPost, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        require_admin().await?;
+        let store = crate::content::use_content_store().await?;
+        actix_web::web::block(move || store.parse_preview(&content))
+            .await?
+            .map_err(ServerFnError::new)
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    {
+        let _ = content;
+        Err(ServerFnError::new(
+            "This function is only available on the server",
+        ))
+    }
+}
+
+/// Writes `content` back to the content source and returns the id of the
+/// entry. New posts (`id` of `None`) are stored as `<date>-<slug>.md`.
+/// Content that doesn't parse, or whose slug another entry already uses,
+/// is rejected.
+#[server(name = SaveAdminEntry, prefix = "/api")]
+pub async fn save_admin_entry(
+    id: Option<String>,
+    content: String,
+) -> Result<String, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        require_admin().await?;
+        let store = crate::content::use_content_store().await?;
+
+        actix_web::web::block(move || {
+            let post = store.parse(&content).map_err(ServerFnError::new)?;
+            let slug = post.meta.slug;
+            let id = match id {
+                Some(id) => id,
+                None => {
+                    let id = format!("{}-{}.md", post.meta.date, slug);
+                    if store.source().read(&id).is_ok() {
+                        return Err(ServerFnError::new(format!("{} already exists", id)));
+                    }
+                    id
+                }
+            };
+            if let Some((other, _)) = store
+                .entries()
+                .into_iter()
+                .find(|(other, other_slug)| *other != id && other_slug.as_deref() == Some(&slug))
+            {
+                return Err(ServerFnError::new(format!(
+                    "slug `{}` is already used by {}",
+                    slug, other
+                )));
+            }
+
+            store.write(&id, &content).map_err(ServerFnError::new)?;
+            Ok(id)
+        })
+        .await?
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    {
+        let _ = (id, content);
+        Err(ServerFnError::new(
+            "This function is only available on the server",
+        ))
+    }
+}
+
+/// Deletes the entry `id` from the content source
+#[server(name = DeleteAdminEntry, prefix = "/api")]
+pub async fn delete_admin_entry(id: String) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        require_admin().await?;
+        let store = crate::content::use_content_store().await?;
+        store.delete(&id).map_err(ServerFnError::new)
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    {
+        let _ = id;
+        Err(ServerFnError::new(
+            "This function is only available on the server",
+        ))
+    }
+}
@@ -0,0 +1,31 @@
+use leptos::server_fn::codec::{GetUrl, Json};
+use leptos::{prelude::ServerFnError, *};
+
+#[allow(unused_imports)]
+use crate::models::author::AuthorListing;
+use crate::server_functions::static_export::StaticExportClient;
+
+/// Fetches an author's profile and the listed posts they wrote or co-wrote.
+/// `None` means there's no author with that id.
+#[server(
+    name = FetchAuthorPosts,
+    prefix = "/api",
+    input = GetUrl,
+    output = Json,
+    client = StaticExportClient
+)]
+pub async fn fetch_author_posts(id: String) -> Result<Option<AuthorListing>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let store = crate::content::use_content_store().await?;
+
+        Ok(store.author_listing(&id, chrono::Utc::now()))
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new(
+            "This function is only available on the server",
+        ))
+    }
+}
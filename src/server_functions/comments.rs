@@ -0,0 +1,120 @@
+use leptos::server_fn::codec::{GetUrl, Json};
+use leptos::{prelude::ServerFnError, *};
+
+#[allow(unused_imports)]
+use crate::models::comment::Comment;
+
+/// Longest name a commenter can give, in characters
+pub const MAX_AUTHOR_LEN: usize = 80;
+/// Longest comment body, in characters
+pub const MAX_BODY_LEN: usize = 5000;
+
+/// Fetches the approved comments on the post with `slug` as threads.
+///
+/// Not part of static exports: comments need a running server.
+#[server(name = ListComments, prefix = "/api", input = GetUrl, output = Json)]
+pub async fn list_comments(slug: String) -> Result<Vec<Comment>, ServerFnError> {
+    #[cfg(feature = "comments")]
+    {
+        let comments = crate::content::use_comment_store().await?;
+        comments.approved(&slug).map_err(ServerFnError::new)
+    }
+
+    #[cfg(not(feature = "comments"))]
+    {
+        let _ = slug;
+        Err(ServerFnError::new(
+            "This server was built without the `comments` feature",
+        ))
+    }
+}
+
+/// Submits a comment on the post with `slug`, optionally as a reply to the
+/// approved comment `parent_id`. It is queued for moderation and only shows
+/// up once approved.
+///
+/// `website` is a honeypot: the field is hidden from readers, so anything
+/// in it was filled in by a bot and the comment is quietly dropped.
+#[server(name = PostComment, prefix = "/api")]
+pub async fn post_comment(
+    slug: String,
+    parent_id: Option<i64>,
+    author: String,
+    body: String,
+    website: String,
+) -> Result<(), ServerFnError> {
+    #[cfg(feature = "comments")]
+    {
+        use actix_web::{web, HttpRequest};
+
+        use crate::config::SiteConfig;
+        use crate::content::rate_limit::client_id;
+
+        if !website.is_empty() {
+            return Ok(());
+        }
+
+        let author = author.trim();
+        let body = body.trim();
+        if author.is_empty() || body.is_empty() {
+            return Err(ServerFnError::new("Please enter your name and a comment"));
+        }
+        if author.chars().count() > MAX_AUTHOR_LEN {
+            return Err(ServerFnError::new(format!(
+                "Names can be at most {} characters",
+                MAX_AUTHOR_LEN
+            )));
+        }
+        if body.chars().count() > MAX_BODY_LEN {
+            return Err(ServerFnError::new(format!(
+                "Comments can be at most {} characters",
+                MAX_BODY_LEN
+            )));
+        }
+
+        let now = chrono::Utc::now();
+        let store = crate::content::use_content_store().await?;
+        if !store
+            .get(&slug)
+            .is_some_and(|post| post.meta.is_published(now))
+        {
+            return Err(ServerFnError::new("Comments are closed on this post"));
+        }
+
+        let comments = crate::content::use_comment_store().await?;
+        if let Some(parent_id) = parent_id {
+            if !comments
+                .is_approved(&slug, parent_id)
+                .map_err(ServerFnError::new)?
+            {
+                return Err(ServerFnError::new("The comment you replied to is gone"));
+            }
+        }
+
+        let config = leptos_actix::extract::<web::Data<SiteConfig>>().await?;
+        let request = leptos_actix::extract::<HttpRequest>().await?;
+        let client = client_id(&request, &config.comments.trusted_proxies);
+
+        let recent = comments
+            .submitted_since(&client, now - chrono::Duration::hours(1))
+            .map_err(ServerFnError::new)?;
+        if recent >= config.comments.per_hour {
+            return Err(ServerFnError::new(
+                "You've commented a lot recently, please try again later",
+            ));
+        }
+
+        comments
+            .add(&slug, parent_id, author, body, &client, now)
+            .map_err(ServerFnError::new)?;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "comments"))]
+    {
+        let _ = (slug, parent_id, author, body, website);
+        Err(ServerFnError::new(
+            "This server was built without the `comments` feature",
+        ))
+    }
+}
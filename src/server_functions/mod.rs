@@ -1 +1,19 @@
+pub mod admin;
+pub mod authors;
+pub mod comments;
+pub mod pages;
 pub mod posts;
+pub mod search;
+pub mod series;
+pub mod static_export;
+pub mod tags;
+
+use leptos::prelude::ServerFnError;
+
+/// The message of a server function error without the error kind prefix
+pub fn error_message(error: &ServerFnError) -> String {
+    match error {
+        ServerFnError::ServerError(message) => message.clone(),
+        error => error.to_string(),
+    }
+}
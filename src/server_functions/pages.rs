@@ -0,0 +1,30 @@
+use leptos::server_fn::codec::{GetUrl, Json};
+use leptos::{prelude::ServerFnError, *};
+
+#[allow(unused_imports)]
+use crate::models::page::Page;
+use crate::server_functions::static_export::StaticExportClient;
+
+/// Fetches a standalone page by its URL path, e.g. `about` or `/legal/privacy`
+#[server(
+    name = FetchPage,
+    prefix = "/api",
+    input = GetUrl,
+    output = Json,
+    client = StaticExportClient
+)]
+pub async fn fetch_page(path: String) -> Result<Option<Page>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let store = crate::content::use_page_store().await?;
+
+        Ok(store.get(&path))
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new(
+            "This function is only available on the server",
+        ))
+    }
+}
@@ -1,27 +1,45 @@
+use leptos::server_fn::codec::{GetUrl, Json};
 use leptos::{prelude::ServerFnError, *};
 
 #[allow(unused_imports)]
-use crate::models::blog::{BlogPost, BlogPostMeta};
+use crate::models::blog::{BlogPost, BlogPostMeta, PostNeighbors};
+use crate::server_functions::static_export::StaticExportClient;
 
 #[server(GetPosts, "/api")]
 async fn get_posts() -> Result<Vec<BlogPost>, ServerFnError> {
-    let blog_path = "content/blog";
+    let store = crate::content::use_content_store().await?;
 
-    let posts = process_posts(blog_path);
-
-    Ok(posts)
+    Ok(store.listed_posts(chrono::Utc::now()))
 }
 
-/// Fetches a single blog post by slug
-#[server(FetchBlogPost, "/api")]
-pub async fn fetch_blog_post(slug: String) -> Result<Option<BlogPost>, ServerFnError> {
+/// Fetches a single blog post by slug.
+///
+/// Drafts and scheduled posts are only returned with a valid `preview` token.
+#[server(
+    name = FetchBlogPost,
+    prefix = "/api",
+    input = GetUrl,
+    output = Json,
+    client = StaticExportClient
+)]
+pub async fn fetch_blog_post(
+    slug: String,
+    preview: Option<String>,
+) -> Result<Option<BlogPost>, ServerFnError> {
     #[cfg(feature = "ssr")]
     {
-        let blog_path = "content/blog";
-        let posts = process_posts(blog_path);
+        use crate::content::preview::{preview_secret, verify_preview};
+
+        let store = crate::content::use_content_store().await?;
+        let now = chrono::Utc::now();
 
-        // Find the post with matching slug
-        let post = posts.into_iter().find(|p| p.meta.slug == slug);
+        let post = store.get(&slug).filter(|post| {
+            post.meta.is_published(now)
+                || match (preview_secret(), preview.as_deref()) {
+                    (Some(secret), Some(token)) => verify_preview(&secret, &slug, token, now),
+                    _ => false,
+                }
+        });
         Ok(post)
     }
 
@@ -33,114 +51,89 @@ pub async fn fetch_blog_post(slug: String) -> Result<Option<BlogPost>, ServerFnE
     }
 }
 
+/// How many related posts are shown under a post
+pub const RELATED_POSTS: usize = 3;
+
+/// Fetches the posts published before and after the post with `slug`,
+/// plus related posts to read next
+#[server(
+    name = FetchPostNeighbors,
+    prefix = "/api",
+    input = GetUrl,
+    output = Json,
+    client = StaticExportClient
+)]
+pub async fn fetch_post_neighbors(slug: String) -> Result<PostNeighbors, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let store = crate::content::use_content_store().await?;
+
+        Ok(store.neighbors(&slug, RELATED_POSTS, chrono::Utc::now()))
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new(
+            "This function is only available on the server",
+        ))
+    }
+}
+
 cfg_if::cfg_if! {
     if #[cfg(feature = "ssr")] {
-        use std::{
-            fs::{self, DirEntry},
-            path::Path,
-        };
         use chrono::NaiveDate;
-
-        pub fn get_posts_file<P: AsRef<Path>>(path: P) -> Vec<DirEntry> {
-            match fs::read_dir(path) {
-                Ok(entries) => {
-                    entries
-                        .filter_map(Result::ok)
-                        .filter(|entry| {
-                            if let Ok(file_type) = entry.file_type() {
-                                file_type.is_file() && entry.path().extension() == Some("md".as_ref())
-                            } else {
-                                false
-                            }
-                        })
-                        .collect()
-                },
-                Err(e) => {
-                    eprintln!("Error reading blog directory: {}", e);
-                    Vec::new()
-                }
+        use crate::content::error::ContentError;
+        use crate::content::markdown::{render_markdown, split_frontmatter};
+        use crate::content::sanitize::Sanitizer;
+        use crate::models::blog::parse_date_or_datetime;
+
+        /// Checks a date field up front so a bad value gets a precise error
+        /// instead of a generic deserialization failure.
+        fn check_date_field(
+            data: &serde_json::Value,
+            field: &str,
+            parse: fn(&str) -> bool,
+        ) -> Result<(), ContentError> {
+            match data.get(field).and_then(|v| v.as_str()) {
+                Some(value) if !parse(value) => Err(ContentError::BadDate {
+                    field: field.to_string(),
+                    value: value.to_string(),
+                }),
+                _ => Ok(()),
             }
         }
 
-        pub fn read_post_content(entry: DirEntry) -> Option<String> {
-            fs::read_to_string(entry.path()).ok()
-        }
-
-        pub fn parse_post_content(content: &str) -> Option<BlogPost> {
-            use gray_matter::engine::YAML;
-            use gray_matter::Matter;
-            use pulldown_cmark::{html, Options, Parser};
-
-            let matter = Matter::<YAML>::new();
-
-            let post_data = match matter.parse(content) {
-                Ok(data) => data,
-                Err(e) => {
-                    eprintln!("Error parsing markdown with gray_matter: {}", e);
-                    return None;
-                }
-            };
+        pub fn parse_post_content(
+            content: &str,
+            sanitizer: Option<&Sanitizer>,
+        ) -> Result<BlogPost, ContentError> {
+            let (data, markdown) = split_frontmatter(content)?;
 
             // Deserialize the frontmatter Value into BlogPostMeta
-            let post_metadata: BlogPostMeta = match post_data.data {
-                Some(data) => {
-                    match serde_json::from_value::<BlogPostMeta>(data) {
-                        Ok(meta) => meta,
-                        Err(e) => {
-                            eprintln!("Error parsing frontmatter into BlogPostMeta: {}", e);
-                            return None;
-                        }
-                    }
-                },
-                None => {
-                    eprintln!("No frontmatter found in post");
-                    return None;
-                }
-            };
+            check_date_field(&data, "date", |v| NaiveDate::parse_from_str(v, "%Y-%m-%d").is_ok())?;
+            check_date_field(&data, "updated", |v| parse_date_or_datetime(v).is_some())?;
+            check_date_field(&data, "publish_at", |v| parse_date_or_datetime(v).is_some())?;
+            let mut post_metadata = serde_json::from_value::<BlogPostMeta>(data)
+                .map_err(|e| ContentError::InvalidFrontmatter { message: e.to_string() })?;
 
             // Convert markdown to HTML on the server
-            let markdown_content = post_data.content;
-            let mut options = Options::empty();
-            options.insert(Options::ENABLE_STRIKETHROUGH);
-            options.insert(Options::ENABLE_TABLES);
-            options.insert(Options::ENABLE_FOOTNOTES);
-            options.insert(Options::ENABLE_TASKLISTS);
-
-            let parser = Parser::new_ext(&markdown_content, options);
-            let mut html_output = String::new();
-            html::push_html(&mut html_output, parser);
-
-            Some(BlogPost {
+            let rendered = render_markdown(&markdown, sanitizer)?;
+            post_metadata.word_count = rendered.stats.word_count;
+            post_metadata.reading_time = rendered.stats.reading_time;
+            if post_metadata.description.trim().is_empty() {
+                post_metadata.description = rendered.stats.excerpt.clone();
+            }
+            post_metadata.excerpt = rendered.stats.excerpt;
+
+            Ok(BlogPost {
                 meta: post_metadata,
-                content: html_output,
+                content: rendered.html,
+                toc: rendered.toc,
             })
         }
 
         pub fn sort_posts(posts: &mut [BlogPost]) {
-            posts.sort_by(|a, b| {
-                let a_date = NaiveDate::parse_from_str(&a.meta.date, "%Y-%m-%d").unwrap();
-                let b_date = NaiveDate::parse_from_str(&b.meta.date, "%Y-%m-%d").unwrap();
-                //reverse sorting
-                a_date.cmp(&b_date)
-            });
-        }
-
-        // Code that compiles only with ssr feature
-        pub fn process_posts<P: AsRef<Path>>(path: P) -> Vec<BlogPost> {
-            let posts_text = get_posts_file(path);
-            let mut posts = Vec::new();
-
-            for entry in posts_text {
-                if let Some(content) = read_post_content(entry) {
-                    if let Some(post) = parse_post_content(&content) {
-                        posts.push(post);
-                    }
-                }
-            }
-
-            sort_posts(&mut posts);
-
-            posts
+            posts.sort_by_key(|p| p.meta.date);
         }
     }
 }
@@ -0,0 +1,64 @@
+use leptos::server_fn::codec::{GetUrl, Json};
+use leptos::{prelude::ServerFnError, *};
+
+use crate::models::search::SearchHit;
+use crate::search::StaticSearchIndex;
+use crate::server_functions::static_export::StaticExportClient;
+
+/// Results returned when the caller doesn't ask for a number
+pub const DEFAULT_SEARCH_LIMIT: usize = 20;
+
+/// Most results a single search returns
+pub const MAX_SEARCH_LIMIT: usize = 50;
+
+/// Searches the titles, tags, descriptions and bodies of listed posts.
+/// Every word of `query` has to match; the last one may be a prefix.
+///
+/// Not part of static exports, which search [`fetch_search_index`] in the
+/// browser instead.
+#[server(name = SearchPosts, prefix = "/api", input = GetUrl, output = Json)]
+pub async fn search_posts(
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<SearchHit>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let store = crate::content::use_content_store().await?;
+        let limit = limit
+            .unwrap_or(DEFAULT_SEARCH_LIMIT)
+            .clamp(1, MAX_SEARCH_LIMIT);
+
+        Ok(store.search(&query, limit, chrono::Utc::now()))
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new(
+            "This function is only available on the server",
+        ))
+    }
+}
+
+/// Search index of the listed posts, which pages of a static export search
+/// in the browser since there is no server to run [`search_posts`]
+#[server(
+    name = FetchSearchIndex,
+    prefix = "/api",
+    input = GetUrl,
+    output = Json,
+    client = StaticExportClient
+)]
+pub async fn fetch_search_index() -> Result<StaticSearchIndex, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let store = crate::content::use_content_store().await?;
+        Ok(store.static_search_index(chrono::Utc::now()))
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new(
+            "This function is only available on the server",
+        ))
+    }
+}
@@ -0,0 +1,31 @@
+use leptos::server_fn::codec::{GetUrl, Json};
+use leptos::{prelude::ServerFnError, *};
+
+#[allow(unused_imports)]
+use crate::models::blog::{slugify, SeriesListing};
+use crate::server_functions::static_export::StaticExportClient;
+
+/// Fetches the listed parts of a series in reading order. `series` may be the
+/// name or its slug; `None` means no listed post is part of it.
+#[server(
+    name = FetchSeries,
+    prefix = "/api",
+    input = GetUrl,
+    output = Json,
+    client = StaticExportClient
+)]
+pub async fn fetch_series(series: String) -> Result<Option<SeriesListing>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let store = crate::content::use_content_store().await?;
+
+        Ok(store.series(&slugify(&series), chrono::Utc::now()))
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new(
+            "This function is only available on the server",
+        ))
+    }
+}
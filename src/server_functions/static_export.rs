@@ -0,0 +1,112 @@
+//! Serving server functions from a static export.
+//!
+//! `leptos-bplate export` writes the response of every read-only server
+//! function call the pages make to a JSON file, named after the call's query
+//! string. Pages rendered by the export carry a `<meta name="static-export">`
+//! tag; when it is present [`StaticExportClient`] fetches those files instead
+//! of calling the server, so client-side navigation keeps working on plain
+//! static hosting.
+
+use std::future::Future;
+
+use futures::{Sink, Stream};
+use leptos::server_fn::client::{browser::BrowserClient, Client};
+use leptos::server_fn::error::FromServerFnError;
+use leptos::server_fn::request::{browser::BrowserRequest, ClientReq};
+use leptos::server_fn::response::browser::BrowserResponse;
+use leptos::server_fn::Bytes;
+
+/// Name of the `<meta>` tag that marks a page as part of a static export
+pub const STATIC_EXPORT_META: &str = "static-export";
+
+/// Path of the file holding the response to a `GET` server function call.
+///
+/// Characters that can't appear in a file name as-is are written as `~XX`,
+/// so every query maps to its own file.
+pub fn static_response_path(path: &str, query: &str) -> String {
+    if query.is_empty() {
+        return format!("{}/index.json", path);
+    }
+
+    let mut name = String::with_capacity(query.len());
+    for byte in query.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'=' => {
+                name.push(byte as char)
+            }
+            byte => name.push_str(&format!("~{:02X}", byte)),
+        }
+    }
+    format!("{}/{}.json", path, name)
+}
+
+/// Server function client that reads exported responses on static pages and
+/// behaves like the default browser client everywhere else
+pub struct StaticExportClient;
+
+impl<E, IS, OS> Client<E, IS, OS> for StaticExportClient
+where
+    E: FromServerFnError + Send,
+    IS: FromServerFnError,
+    OS: FromServerFnError,
+{
+    type Request = BrowserRequest;
+    type Response = BrowserResponse;
+
+    async fn send(req: Self::Request) -> Result<Self::Response, E> {
+        let req = match exported_path(&req) {
+            Some(path) => <BrowserRequest as ClientReq<E>>::try_new_req_query(
+                &path,
+                "",
+                "application/json",
+                "",
+                http::Method::GET,
+            )?,
+            None => req,
+        };
+        <BrowserClient as Client<E, IS, OS>>::send(req).await
+    }
+
+    fn open_websocket(
+        path: &str,
+    ) -> impl Future<
+        Output = Result<
+            (
+                impl Stream<Item = Result<Bytes, Bytes>> + Send + 'static,
+                impl Sink<Bytes> + Send + 'static,
+            ),
+            E,
+        >,
+    > + Send {
+        <BrowserClient as Client<E, IS, OS>>::open_websocket(path)
+    }
+
+    fn spawn(future: impl Future<Output = ()> + Send + 'static) {
+        <BrowserClient as Client<E, IS, OS>>::spawn(future)
+    }
+}
+
+/// Where the exported response to `req` lives, if this page is part of a
+/// static export and the request can be answered from a file
+fn exported_path(req: &BrowserRequest) -> Option<String> {
+    if req.method() != http::Method::GET || !is_static_export() {
+        return None;
+    }
+
+    let url = web_sys::Url::new(&req.url()).ok()?;
+    let query = url.search();
+    Some(static_response_path(
+        &url.pathname(),
+        query.strip_prefix('?').unwrap_or(&query),
+    ))
+}
+
+/// Whether the current page is part of a static export. Only meaningful in
+/// the browser.
+pub fn is_static_export() -> bool {
+    leptos::prelude::document()
+        .query_selector(&format!("meta[name=\"{}\"]", STATIC_EXPORT_META))
+        .ok()
+        .flatten()
+        .is_some()
+}
@@ -0,0 +1,138 @@
+use leptos::server_fn::codec::{GetUrl, Json};
+use leptos::{prelude::ServerFnError, *};
+
+#[allow(unused_imports)]
+use crate::models::blog::{slugify, BlogPostMeta, TagListing, TagSummary};
+use crate::server_functions::static_export::StaticExportClient;
+
+/// Fetches every tag used by a listed post, most used first
+#[server(
+    name = FetchTags,
+    prefix = "/api",
+    input = GetUrl,
+    output = Json,
+    client = StaticExportClient
+)]
+pub async fn fetch_tags() -> Result<Vec<TagSummary>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let store = crate::content::use_content_store().await?;
+
+        Ok(tag_summaries(&store.listed_metas(chrono::Utc::now())))
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new(
+            "This function is only available on the server",
+        ))
+    }
+}
+
+/// Fetches the listed posts with a tag. `tag` may be written in any case or
+/// as its slug; `None` means no listed post uses it.
+#[server(
+    name = FetchPostsByTag,
+    prefix = "/api",
+    input = GetUrl,
+    output = Json,
+    client = StaticExportClient
+)]
+pub async fn fetch_posts_by_tag(tag: String) -> Result<Option<TagListing>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let store = crate::content::use_content_store().await?;
+        let slug = slugify(&tag);
+
+        let posts: Vec<BlogPostMeta> = store
+            .listed_metas(chrono::Utc::now())
+            .into_iter()
+            .filter(|meta| meta.has_tag(&slug))
+            .collect();
+
+        Ok(tag_summaries(&posts)
+            .into_iter()
+            .find(|summary| summary.slug == slug)
+            .map(|tag| TagListing { tag, posts }))
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new(
+            "This function is only available on the server",
+        ))
+    }
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        /// Counts tags across `posts`, merging spellings that share a slug.
+        /// Sorted by count, then alphabetically.
+        pub fn tag_summaries(posts: &[BlogPostMeta]) -> Vec<TagSummary> {
+            let mut tags: Vec<TagSummary> = Vec::new();
+
+            for post in posts {
+                let mut seen = Vec::new();
+                for name in &post.tags {
+                    let slug = slugify(name);
+                    if slug.is_empty() || seen.contains(&slug) {
+                        continue;
+                    }
+
+                    match tags.iter_mut().find(|t| t.slug == slug) {
+                        Some(tag) => tag.count += 1,
+                        None => tags.push(TagSummary {
+                            name: name.clone(),
+                            slug: slug.clone(),
+                            count: 1,
+                        }),
+                    }
+                    seen.push(slug);
+                }
+            }
+
+            tags.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.slug.cmp(&b.slug)));
+            tags
+        }
+    }
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use super::*;
+
+    fn post(tags: &[&str]) -> BlogPostMeta {
+        serde_json::from_value(serde_json::json!({
+            "slug": "post",
+            "title": "Post",
+            "date": "2024-01-01",
+            "tags": tags,
+        }))
+        .unwrap()
+    }
+
+    fn counts(tags: &[TagSummary]) -> Vec<(&str, &str, usize)> {
+        tags.iter()
+            .map(|tag| (tag.name.as_str(), tag.slug.as_str(), tag.count))
+            .collect()
+    }
+
+    #[test]
+    fn merges_spellings_of_a_tag() {
+        let tags = tag_summaries(&[
+            post(&["Web Assembly", "rust"]),
+            post(&["web-assembly", "WEB_ASSEMBLY"]),
+            post(&["Rust", "!!"]),
+            post(&["Leptos"]),
+        ]);
+        assert_eq!(
+            counts(&tags),
+            [
+                ("rust", "rust", 2),
+                ("Web Assembly", "web-assembly", 2),
+                ("Leptos", "leptos", 1),
+            ]
+        );
+        assert!(tag_summaries(&[]).is_empty());
+    }
+}